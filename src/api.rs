@@ -0,0 +1,715 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Cursor;
+
+use rocket::http::{ContentType, Status};
+use rocket::response::{content, Responder, Response};
+
+use serde::Serialize;
+use serde_json;
+
+use summary::{titlegenerator, Algorithm, Category, Keyphrase, Keyword, Length, Normalization, Order, Phrase, Span, Summary, SummaryError, SummaryOptions, Topic};
+
+/* texts larger than this are rejected with 413, override with SUMMARY_MAX_TEXT_LENGTH */
+pub const DEFAULT_MAX_TEXT_LENGTH: usize = 512 * 1024;
+
+pub struct Limits {
+    pub max_text_length: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SummaryOperation {
+    // only used by /summary/batch to key the results
+    pub id: Option<String>,
+
+    // either a text or several related documents summarized together
+    #[serde(default)]
+    pub text: String,
+    pub documents: Option<Vec<Document>>,
+
+    pub num_titles: Option<u32>,
+    pub num_keywords: Option<u32>,
+    // number of multi-word keyphrases, all by default
+    pub num_keyphrases: Option<u32>,
+    // number of broad topic labels derived from the keywords, none by default;
+    // topics_unavailable if the dictionary has no noun synsets or lexicographer files
+    pub num_topics: Option<u32>,
+
+    // summary length, at most one of: number of phrases (default 3), fraction
+    // of the phrases of the text, word budget or character budget
+    pub num_phrases: Option<u32>,
+    pub ratio: Option<f64>,
+    pub max_words: Option<u32>,
+    pub max_chars: Option<u32>,
+
+    // "document" (default) or "score", i.e. best phrase first
+    pub order: Option<String>,
+
+    // "frequency" (default) or "textrank"
+    pub algorithm: Option<String>,
+
+    // "none" (default), "average", "log" or "target" (with target_length content words);
+    // target_length is invalid with the others
+    pub normalization: Option<String>,
+    pub target_length: Option<u32>,
+
+    // between 0 and 1, lower values favour phrases that repeat less of the phrases already picked
+    pub lambda: Option<f64>,
+
+    // boosts for the first phrases of the text and of every paragraph, 0 (default) disables them
+    pub lead_bias: Option<f64>,
+    pub paragraph_bias: Option<f64>,
+
+    // favours phrases containing the words of the query
+    pub query: Option<String>,
+
+    // counts synonyms and derived words as one keyword, listed in variants
+    pub merge_synonyms: Option<bool>,
+
+    // adds the share of the words in each lexicographer category (noun.feeling, noun.body, ...) to the result
+    pub include_categories: Option<bool>,
+
+    // adds the weights behind phrases and keywords to the result
+    pub include_scores: Option<bool>,
+
+    // adds the positions of phrases and keywords in the text to the result
+    pub include_offsets: Option<bool>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Document {
+    // identifies the document phrases are from, defaults to its index
+    pub id: Option<String>,
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct SummaryResult {
+    pub phrases: Vec<String>,
+    pub keywords: Vec<String>,
+    pub keyphrases: Vec<String>,
+
+    // the words merged into each keyword, only with merge_synonyms and only for keywords with variants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variants: Option<BTreeMap<String, Vec<String>>>,
+
+    // the id of the document each phrase is from, only when summarizing documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sources: Option<Vec<String>>,
+
+    pub titles: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topics: Option<Vec<TopicLabel>>,
+
+    // largest first, empty if the dictionary has no lexicographer files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<CategoryShare>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scores: Option<Scores>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offsets: Option<Offsets>
+}
+
+#[derive(Serialize)]
+pub struct TopicLabel {
+    pub label: String,
+    pub score: f64,
+    // the keywords the topic was derived from
+    pub keywords: Vec<String>,
+}
+
+impl From<Topic> for TopicLabel {
+    fn from(topic: Topic) -> TopicLabel {
+        TopicLabel {
+            label: topic.label,
+            score: topic.score,
+            keywords: topic.keywords,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CategoryShare {
+    pub name: String,
+    pub share: f64,
+}
+
+impl From<Category> for CategoryShare {
+    fn from(category: Category) -> CategoryShare {
+        CategoryShare {
+            name: category.name.to_string(),
+            share: category.share,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct PhraseScore {
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document: Option<String>,
+    index: usize,
+    score: f64,
+}
+
+impl From<Phrase> for PhraseScore {
+    fn from(phrase: Phrase) -> PhraseScore {
+        PhraseScore {
+            text: phrase.text,
+            document: None,
+            index: phrase.index,
+            score: phrase.score,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct KeywordScore {
+    word: String,
+    frequency: u32,
+    score: f64,
+    noun: bool,
+    verb: bool,
+    adj: bool,
+    adv: bool,
+}
+
+impl From<Keyword> for KeywordScore {
+    fn from(keyword: Keyword) -> KeywordScore {
+        KeywordScore {
+            word: keyword.word,
+            frequency: keyword.frequency,
+            score: keyword.score,
+            noun: keyword.noun,
+            verb: keyword.verb,
+            adj: keyword.adj,
+            adv: keyword.adv,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct KeyphraseScore {
+    phrase: String,
+    frequency: u32,
+    score: f64,
+}
+
+impl From<Keyphrase> for KeyphraseScore {
+    fn from(keyphrase: Keyphrase) -> KeyphraseScore {
+        KeyphraseScore {
+            phrase: keyphrase.phrase,
+            frequency: keyphrase.frequency,
+            score: keyphrase.score,
+        }
+    }
+}
+
+/* same order as phrases, keywords and keyphrases */
+#[derive(Serialize)]
+pub struct Scores {
+    phrases: Vec<PhraseScore>,
+    keywords: Vec<KeywordScore>,
+    keyphrases: Vec<KeyphraseScore>,
+}
+
+/*
+    character (not byte) offsets into the submitted text (or document), end
+    exclusive; phrases the sentence splitter could not locate in the text
+    have none, so they are null and keyword occurrences in them left out
+*/
+#[derive(Serialize)]
+pub struct TextSpan {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    document: Option<String>,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Serialize)]
+pub struct KeywordOffsets {
+    word: String,
+    occurrences: Vec<TextSpan>,
+}
+
+#[derive(Serialize)]
+pub struct KeyphraseOffsets {
+    phrase: String,
+    occurrences: Vec<TextSpan>,
+}
+
+/* same order as phrases, keywords and keyphrases */
+#[derive(Serialize)]
+pub struct Offsets {
+    phrases: Vec<Option<TextSpan>>,
+    keywords: Vec<KeywordOffsets>,
+    keyphrases: Vec<KeyphraseOffsets>,
+}
+
+/* maps byte offsets of the text to character offsets */
+struct CharOffsets {
+    char_starts: Vec<usize>,
+}
+
+impl CharOffsets {
+    fn new(text: &str) -> CharOffsets {
+        CharOffsets { char_starts: text.char_indices().map(|(i, _)| i).collect() }
+    }
+
+    fn offset(&self, byte: usize) -> usize {
+        match self.char_starts.binary_search(&byte) {
+            Ok(i) | Err(i) => i,
+        }
+    }
+
+    /* None for the empty spans of text that could not be located */
+    fn span(&self, span: &Span) -> Option<TextSpan> {
+        if span.is_empty() {
+            return None;
+        }
+
+        Some(TextSpan { document: None, start: self.offset(span.start), end: self.offset(span.end) })
+    }
+}
+
+#[derive(Serialize)]
+pub struct ErrorBody {
+    code: &'static str,
+    status: u16,
+    message: String,
+}
+
+impl ErrorBody {
+    /* errors raised before summarize is reached, like a body that is not JSON */
+    pub fn new(code: &'static str, status: u16, message: String) -> ErrorBody {
+        ErrorBody { code, status, message }
+    }
+}
+
+impl<'a> From<&'a SummaryError> for ErrorBody {
+    fn from(error: &'a SummaryError) -> ErrorBody {
+        ErrorBody::new(error.code(), error.status(), error.to_string())
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: ErrorBody,
+}
+
+#[derive(Serialize)]
+pub struct BatchItemResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<SummaryResult>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorBody>
+}
+
+#[derive(Serialize)]
+pub struct BatchResult {
+    pub results: BTreeMap<String, BatchItemResult>
+}
+
+/* the id of the operation at `index` of a batch: its own or its position */
+pub fn batch_id(operation: &SummaryOperation, index: usize) -> String {
+    operation.id.clone().unwrap_or(index.to_string())
+}
+
+/*
+    Ids of several operations of a batch, like two operations with "id": "a"
+    or one with "id": "1" and a second operation without an id. Their
+    results would overwrite each other, so they all fail instead.
+*/
+pub fn duplicate_ids(operations: &[SummaryOperation]) -> HashSet<String> {
+    let mut seen = HashSet::new();
+
+    operations
+        .iter()
+        .enumerate()
+        .map(|(i, operation)| batch_id(operation, i))
+        .filter(|id| !seen.insert(id.clone()))
+        .collect()
+}
+
+/* a SummaryError on its way to the client */
+#[derive(Debug)]
+pub struct ApiError(pub SummaryError);
+
+impl From<SummaryError> for ApiError {
+    fn from(error: SummaryError) -> ApiError {
+        ApiError(error)
+    }
+}
+
+/*
+    Errors are rendered as
+
+        {"error": {"code": "empty_text", "status": 400, "message": "..."}}
+
+    with the HTTP status mirrored in the response line.
+*/
+impl<'r> Responder<'r> for ErrorBody {
+    fn respond(self) -> Result<Response<'r>, Status> {
+        let status = Status::from_code(self.status).unwrap_or(Status::InternalServerError);
+
+        let body = match serde_json::to_string(&ErrorResponse { error: self }) {
+            Ok(body) => body,
+            // should never happen, but never answer an error with "{}"
+            Err(_) => return Err(Status::InternalServerError),
+        };
+
+        Response::build()
+            .status(status)
+            .header(ContentType::JSON)
+            .sized_body(Cursor::new(body))
+            .ok()
+    }
+}
+
+impl<'r> Responder<'r> for ApiError {
+    fn respond(self) -> Result<Response<'r>, Status> {
+        ErrorBody::from(&self.0).respond()
+    }
+}
+
+fn bias(name: &'static str, value: Option<f64>) -> Result<f64, SummaryError> {
+    match value {
+        Some(bias) if !(bias >= 0.0) => Err(SummaryError::InvalidOption { name, value: bias.to_string() }),
+        bias => Ok(bias.unwrap_or(0.0)),
+    }
+}
+
+pub fn summarize(summary: &Summary, input: &SummaryOperation, limits: &Limits) -> Result<SummaryResult, SummaryError> {
+    let num_titles = input.num_titles.unwrap_or(0u32);
+
+    /* (id, text) of every document, ids are only reported when documents were given */
+    let documents = match input.documents {
+        Some(ref documents) => {
+            if !input.text.is_empty() {
+                return Err(SummaryError::ConflictingOptions(vec!["text", "documents"]));
+            }
+
+            documents
+                .iter()
+                .enumerate()
+                .map(|(i, document)| (Some(document.id.clone().unwrap_or(i.to_string())), document.text.as_str()))
+                .collect::<Vec<(Option<String>, &str)>>()
+        }
+        None => vec![(None, input.text.as_str())],
+    };
+
+    let texts = documents.iter().map(|&(_, text)| text).collect::<Vec<&str>>();
+    let length = texts.iter().map(|text| text.len()).sum::<usize>();
+
+    if texts.iter().all(|text| text.trim().is_empty()) {
+        return Err(SummaryError::EmptyText);
+    }
+
+    if length > limits.max_text_length {
+        return Err(SummaryError::TextTooLarge { length, limit: limits.max_text_length });
+    }
+
+    let summary_length = {
+        let lengths = vec![
+            ("num_phrases", input.num_phrases.map(Length::Phrases)),
+            ("ratio", input.ratio.map(Length::Ratio)),
+            ("max_words", input.max_words.map(Length::Words)),
+            ("max_chars", input.max_chars.map(Length::Chars)),
+        ]
+        .into_iter()
+        .filter(|&(_, length)| length.is_some())
+        .collect::<Vec<(&'static str, Option<Length>)>>();
+
+        match lengths.len() {
+            0 => Length::default(),
+            1 => lengths[0].1.unwrap(),
+            _ => return Err(SummaryError::ConflictingOptions(lengths.iter().map(|&(name, _)| name).collect())),
+        }
+    };
+
+    if let Length::Ratio(ratio) = summary_length {
+        if !(ratio > 0.0 && ratio <= 1.0) {
+            return Err(SummaryError::InvalidOption { name: "ratio", value: ratio.to_string() });
+        }
+    }
+
+    let options = SummaryOptions {
+        length: summary_length,
+        order: match input.order {
+            Some(ref order) => order.parse::<Order>()?,
+            None => Order::default(),
+        },
+        // titles are built from every keyword, the reported ones are truncated below
+        num_keywords: None,
+        num_keyphrases: input.num_keyphrases,
+        num_topics: input.num_topics.unwrap_or(0),
+        algorithm: match input.algorithm {
+            Some(ref algorithm) => algorithm.parse::<Algorithm>()?,
+            None => Algorithm::default(),
+        },
+        normalization: match (input.normalization.as_ref(), input.target_length) {
+            (Some(normalization), length) => match (normalization.parse::<Normalization>()?, length) {
+                (Normalization::TargetLength(_), Some(length)) => Normalization::TargetLength(length as usize),
+                // only target normalization has a length, it would be ignored
+                (_, Some(length)) => return Err(SummaryError::InvalidOption { name: "target_length", value: length.to_string() }),
+                (normalization, None) => normalization,
+            },
+            (None, Some(length)) => return Err(SummaryError::InvalidOption { name: "target_length", value: length.to_string() }),
+            (None, None) => Normalization::default(),
+        },
+        lambda: match input.lambda {
+            Some(lambda) if !(lambda >= 0.0 && lambda <= 1.0) =>
+                return Err(SummaryError::InvalidOption { name: "lambda", value: lambda.to_string() }),
+            lambda => lambda,
+        },
+        lead_bias: bias("lead_bias", input.lead_bias)?,
+        paragraph_bias: bias("paragraph_bias", input.paragraph_bias)?,
+        query: input.query.clone(),
+        merge_synonyms: input.merge_synonyms.unwrap_or(false),
+        categories: input.include_categories.unwrap_or(false),
+    };
+
+    // an empty list would read as "no topics found"
+    if options.num_topics > 0 && !summary.has_topics() {
+        return Err(SummaryError::TopicsUnavailable);
+    }
+
+    let mut digest = match input.documents {
+        Some(_) => summary.digest_documents(&texts, &options),
+        None => summary.digest(&texts[0], &options),
+    };
+
+    let titles = {
+        if num_titles == 0 {
+            None
+        } else {
+            let keywords = digest.keywords.iter().map(|keyword| keyword.word.clone()).collect::<Vec<String>>();
+
+            Some(titlegenerator::build_titles(&keywords, num_titles)?)
+        }
+    };
+
+    if let Some(num_keywords) = input.num_keywords {
+        digest.keywords.truncate(num_keywords as usize);
+    }
+
+    let document_id = |document: usize| documents[document].0.clone();
+
+    let phrases = digest.phrases.iter().map(|phrase| phrase.text.clone()).collect();
+    let sources = input.documents
+        .as_ref()
+        .map(|_| digest.phrases.iter().filter_map(|phrase| document_id(phrase.document)).collect());
+    let keywords = digest.keywords.iter().map(|keyword| keyword.word.clone()).collect::<Vec<String>>();
+    let variants = if options.merge_synonyms {
+        Some(digest.keywords
+            .iter()
+            .filter(|keyword| !keyword.variants.is_empty())
+            .map(|keyword| (keyword.word.clone(), keyword.variants.clone()))
+            .collect())
+    } else {
+        None
+    };
+    let topics = if options.num_topics > 0 {
+        Some(digest.topics.into_iter().map(TopicLabel::from).collect())
+    } else {
+        None
+    };
+    let categories = if options.categories {
+        Some(digest.categories.into_iter().map(CategoryShare::from).collect())
+    } else {
+        None
+    };
+    let keyphrases = digest.keyphrases.iter().map(|keyphrase| keyphrase.phrase.clone()).collect();
+
+    let offsets = {
+        if input.include_offsets.unwrap_or(false) {
+            let chars = texts.iter().map(|text| CharOffsets::new(text)).collect::<Vec<CharOffsets>>();
+
+            let span = |document: usize, span: &Span| {
+                chars[document].span(span).map(|span| TextSpan { document: document_id(document), ..span })
+            };
+
+            Some(Offsets {
+                phrases: digest.phrases.iter().map(|phrase| span(phrase.document, &phrase.span)).collect(),
+                keywords: digest.keywords
+                    .iter()
+                    .map(|keyword| KeywordOffsets {
+                        word: keyword.word.clone(),
+                        occurrences: keyword.occurrences.iter().filter_map(|&(document, ref occurrence)| span(document, occurrence)).collect(),
+                    })
+                    .collect(),
+                keyphrases: digest.keyphrases
+                    .iter()
+                    .map(|keyphrase| KeyphraseOffsets {
+                        phrase: keyphrase.phrase.clone(),
+                        occurrences: keyphrase.occurrences.iter().filter_map(|&(document, ref occurrence)| span(document, occurrence)).collect(),
+                    })
+                    .collect(),
+            })
+        } else {
+            None
+        }
+    };
+
+    let scores = {
+        if input.include_scores.unwrap_or(false) {
+            Some(Scores {
+                phrases: digest.phrases
+                    .into_iter()
+                    .map(|phrase| PhraseScore { document: document_id(phrase.document), ..PhraseScore::from(phrase) })
+                    .collect(),
+                keywords: digest.keywords.into_iter().map(KeywordScore::from).collect(),
+                keyphrases: digest.keyphrases.into_iter().map(KeyphraseScore::from).collect(),
+            })
+        } else {
+            None
+        }
+    };
+
+    Ok(SummaryResult { phrases, keywords, keyphrases, variants, sources, titles, topics, categories, scores, offsets })
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<content::JSON<String>, ApiError> {
+    serde_json::to_string(value)
+        .map(content::JSON)
+        .map_err(|e| ApiError(SummaryError::Internal(format!("could not serialize result: {}", e))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{duplicate_ids, summarize, CharOffsets, ErrorBody, ErrorResponse, Limits, SummaryOperation, SummaryResult};
+
+    use serde_json;
+
+    use summary::{Span, Summary, SummaryError};
+
+    const TEXT: &'static str = "Red apples grow on tall trees. Green pears grow on short trees. The orchard needs water.";
+
+    fn summary() -> Summary {
+        Summary::with_dictionary("tests/fixtures/dict").unwrap()
+    }
+
+    fn operation(json: &str) -> SummaryOperation {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn error(result: Result<SummaryResult, SummaryError>) -> (&'static str, u16) {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => (e.code(), e.status()),
+        }
+    }
+
+    #[test]
+    fn errors_have_a_code_and_a_status() {
+        let summary = summary();
+        let limits = Limits { max_text_length: 1000 };
+
+        assert_eq!(error(summarize(&summary, &operation(r#"{"text": " \n "}"#), &limits)), ("empty_text", 400));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}"}}"#, TEXT)), &Limits { max_text_length: 10 })),
+                   ("text_too_large", 413));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "num_phrases": 2, "ratio": 0.5}}"#, TEXT)), &limits)),
+                   ("conflicting_options", 400));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "lambda": 2}}"#, TEXT)), &limits)),
+                   ("invalid_option", 400));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "order": "random"}}"#, TEXT)), &limits)),
+                   ("invalid_option", 400));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "target_length": 8}}"#, TEXT)), &limits)),
+                   ("invalid_option", 400));
+        assert_eq!(error(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "normalization": "log", "target_length": 8}}"#, TEXT)), &limits)),
+                   ("invalid_option", 400));
+        assert!(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "normalization": "target", "target_length": 8}}"#, TEXT)), &limits).is_ok());
+        // the fixture dictionary has no data.noun, but noun lexicographer files to compile the hypernyms from
+        assert!(summarize(&summary, &operation(&format!(r#"{{"text": "{}", "num_topics": 2}}"#, TEXT)), &limits).is_ok());
+        assert_eq!(error(summarize(&Summary::with_dictionary("tests/fixtures/synonyms-dict").unwrap(),
+                                   &operation(&format!(r#"{{"text": "{}", "num_topics": 2}}"#, TEXT)), &limits)),
+                   ("topics_unavailable", 501));
+    }
+
+    #[test]
+    fn errors_are_wrapped_in_an_error_object() {
+        let rendered = |error| serde_json::to_string(&ErrorResponse { error }).unwrap();
+
+        assert_eq!(rendered(ErrorBody::from(&SummaryError::EmptyText)),
+                   r#"{"error":{"code":"empty_text","status":400,"message":"the text to summarize is empty"}}"#);
+        assert_eq!(rendered(ErrorBody::new("not_found", 404, "no route".to_string())),
+                   r#"{"error":{"code":"not_found","status":404,"message":"no route"}}"#);
+    }
+
+    #[test]
+    fn shared_ids_are_duplicates() {
+        let operations = vec![
+            operation(r#"{"id": "1", "text": "a"}"#),
+            operation(r#"{"text": "b"}"#),
+            operation(r#"{"id": "x", "text": "c"}"#),
+            operation(r#"{"id": "x", "text": "d"}"#),
+            operation(r#"{"id": "y", "text": "e"}"#),
+        ];
+
+        let mut duplicates = duplicate_ids(&operations).into_iter().collect::<Vec<String>>();
+        duplicates.sort();
+
+        assert_eq!(duplicates, vec!["1", "x"]);
+        assert!(duplicate_ids(&operations[2..3]).is_empty());
+    }
+
+    #[test]
+    fn byte_offsets_become_char_offsets() {
+        // "é", "à" and "ü" take two bytes each
+        let chars = CharOffsets::new("né à Zürich");
+
+        let span = chars.span(&Span { start: 7, end: 14 }).unwrap();
+        assert_eq!((span.start, span.end), (5, 11));
+
+        let span = chars.span(&Span { start: 0, end: 3 }).unwrap();
+        assert_eq!((span.start, span.end), (0, 2));
+
+        assert!(chars.span(&Span { start: 4, end: 4 }).is_none());
+    }
+
+    #[test]
+    fn offsets_are_reported_in_chars() {
+        let text = "Grüße aus Zürich. Red apples grow on tall trees. Green pears grow on short trees.";
+        let json = format!(r#"{{"text": "{}", "num_phrases": 1, "order": "score", "include_offsets": true}}"#, text);
+
+        let result = match summarize(&summary(), &operation(&json), &Limits { max_text_length: 1000 }) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        };
+
+        let offsets = result.offsets.unwrap();
+        let span = offsets.phrases[0].as_ref().unwrap();
+        let phrase = text.chars().skip(span.start).take(span.end - span.start).collect::<String>();
+
+        assert_eq!(phrase, result.phrases[0]);
+
+        for keyword in offsets.keywords.iter() {
+            for occurrence in keyword.occurrences.iter() {
+                assert!(occurrence.end > occurrence.start && occurrence.end <= text.chars().count());
+            }
+        }
+    }
+
+    #[test]
+    fn titles_are_built_from_every_keyword() {
+        let summary = summary();
+        let limits = Limits { max_text_length: 1000 };
+
+        let json = |num_keywords| format!(r#"{{"text": "{}", "num_keywords": {}, "num_titles": 2}}"#, TEXT, num_keywords);
+
+        let all = match summarize(&summary, &operation(&json(100)), &limits) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        };
+        let none = match summarize(&summary, &operation(&json(0)), &limits) {
+            Ok(result) => result,
+            Err(e) => panic!("{}", e),
+        };
+
+        assert!(none.keywords.is_empty());
+        assert_eq!(none.titles, all.titles);
+    }
+}
@@ -0,0 +1,567 @@
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process;
+
+use serde_json;
+
+use summary::{Relation, Summary, SummaryBuilder, ADJ, ADV, NOUN, VERB};
+
+use api::{summarize, CategoryShare, Document, Limits, SummaryOperation, SummaryResult};
+
+/* the text and markdown output only list the largest categories */
+const CATEGORIES_SHOWN: usize = 5;
+
+const USAGE: &'static str = "\
+Usage:
+    summary                       run the HTTP server
+    summary serve                 run the HTTP server
+    summary summarize [options] [FILE...]
+                                  summarize FILE (or stdin if omitted or -),
+                                  several FILEs are summarized together
+    summary idf [--dict PATH] CORPUS OUTPUT
+                                  count document frequencies of the files in
+                                  the directory CORPUS, for use with --idf
+    summary define [--dict PATH] WORD...
+                                  look up the meanings of WORD in WordNet
+
+Options:
+    --phrases N                   number of phrases to extract (default 3)
+    --ratio X                     extract this fraction (0..1) of the phrases
+    --max-words N                 extract phrases totalling at most N words
+    --max-chars N                 extract phrases totalling at most N characters
+    --order document|score        order of the phrases (default document)
+    --titles N                    number of titles to generate (default 0)
+    --keywords N                  maximum number of keywords (default all)
+    --keyphrases N                maximum number of multi-word keyphrases (default all)
+    --topics N                    number of broad topics the keywords belong to (default 0)
+    --algorithm NAME              frequency (default) or textrank
+    --normalization NAME          none (default), average, log or target
+    --target-length N             phrase length favoured by target (default 12)
+    --lambda X                    0..1, lower values avoid repetitive phrases
+    --lead-bias X                 boost for the first phrases of the text (default 0)
+    --paragraph-bias X            boost for the first phrase of paragraphs (default 0)
+    --query TEXT                  favour phrases containing the words of TEXT
+    --merge-synonyms              count synonyms and derived words as one keyword
+    --categories                  profile the words by WordNet category (needs dict/dbfiles)
+    --dict PATH                   WordNet dictionary (default $WORDNET_PATH or ./dict/)
+    --idf FILE                    document frequencies from summary idf (default $IDF_PATH)
+    --format json|text|markdown   output format (default text)
+    --scores                      include phrase and keyword weights (json only)
+    --offsets                     include phrase and keyword positions (json only)
+";
+
+enum Format {
+    Json,
+    Text,
+    Markdown,
+}
+
+struct Options {
+    inputs: Vec<String>,
+    phrases: Option<u32>,
+    ratio: Option<f64>,
+    max_words: Option<u32>,
+    max_chars: Option<u32>,
+    order: Option<String>,
+    titles: u32,
+    keywords: Option<u32>,
+    keyphrases: Option<u32>,
+    topics: u32,
+    algorithm: Option<String>,
+    normalization: Option<String>,
+    target_length: Option<u32>,
+    lambda: Option<f64>,
+    lead_bias: Option<f64>,
+    paragraph_bias: Option<f64>,
+    query: Option<String>,
+    merge_synonyms: bool,
+    categories: bool,
+    dict: Option<String>,
+    idf: Option<String>,
+    format: Format,
+    scores: bool,
+    offsets: bool,
+}
+
+fn usage_error(message: &str) -> ! {
+    let _ = writeln!(io::stderr(), "summary: {}\n\n{}", message, USAGE);
+    process::exit(2)
+}
+
+fn fail(message: &str) -> ! {
+    let _ = writeln!(io::stderr(), "summary: {}", message);
+    process::exit(1)
+}
+
+fn parse_count(flag: &str, value: Option<&String>) -> u32 {
+    match value.map(|value| value.parse::<u32>()) {
+        Some(Ok(count)) => count,
+        Some(Err(_)) => usage_error(&format!("{} expects a non-negative number", flag)),
+        None => usage_error(&format!("{} expects a value", flag)),
+    }
+}
+
+fn parse_number(flag: &str, value: Option<&String>) -> f64 {
+    match value.map(|value| value.parse::<f64>()) {
+        Some(Ok(number)) => number,
+        Some(Err(_)) => usage_error(&format!("{} expects a number", flag)),
+        None => usage_error(&format!("{} expects a value", flag)),
+    }
+}
+
+fn parse_options(args: &[String]) -> Options {
+    let mut options = Options {
+        inputs: Vec::new(),
+        phrases: None,
+        ratio: None,
+        max_words: None,
+        max_chars: None,
+        order: None,
+        titles: 0,
+        keywords: None,
+        keyphrases: None,
+        topics: 0,
+        algorithm: None,
+        normalization: None,
+        target_length: None,
+        lambda: None,
+        lead_bias: None,
+        paragraph_bias: None,
+        query: None,
+        merge_synonyms: false,
+        categories: false,
+        // the library leaves the environment alone, the command line reads it
+        dict: env::var("WORDNET_PATH").ok(),
+        idf: env::var("IDF_PATH").ok(),
+        format: Format::Text,
+        scores: false,
+        offsets: false,
+    };
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--phrases" => options.phrases = Some(parse_count(arg, args.next())),
+            "--ratio" => options.ratio = Some(parse_number(arg, args.next())),
+            "--max-words" => options.max_words = Some(parse_count(arg, args.next())),
+            "--max-chars" => options.max_chars = Some(parse_count(arg, args.next())),
+            "--order" => match args.next() {
+                Some(order) => options.order = Some(order.clone()),
+                None => usage_error("--order expects one of document, score"),
+            },
+            "--titles" => options.titles = parse_count(arg, args.next()),
+            "--keywords" => options.keywords = Some(parse_count(arg, args.next())),
+            "--keyphrases" => options.keyphrases = Some(parse_count(arg, args.next())),
+            "--topics" => options.topics = parse_count(arg, args.next()),
+            "--algorithm" => match args.next() {
+                Some(algorithm) => options.algorithm = Some(algorithm.clone()),
+                None => usage_error("--algorithm expects a name"),
+            },
+            "--normalization" => match args.next() {
+                Some(normalization) => options.normalization = Some(normalization.clone()),
+                None => usage_error("--normalization expects a name"),
+            },
+            "--target-length" => options.target_length = Some(parse_count(arg, args.next())),
+            "--lambda" => options.lambda = Some(parse_number(arg, args.next())),
+            "--lead-bias" => options.lead_bias = Some(parse_number(arg, args.next())),
+            "--paragraph-bias" => options.paragraph_bias = Some(parse_number(arg, args.next())),
+            "--query" => match args.next() {
+                Some(query) => options.query = Some(query.clone()),
+                None => usage_error("--query expects a text"),
+            },
+            "--dict" => match args.next() {
+                Some(path) => options.dict = Some(path.clone()),
+                None => usage_error("--dict expects a path"),
+            },
+            "--idf" => match args.next() {
+                Some(path) => options.idf = Some(path.clone()),
+                None => usage_error("--idf expects a path"),
+            },
+            "--format" => options.format = match args.next().map(|format| format.as_str()) {
+                Some("json") => Format::Json,
+                Some("text") => Format::Text,
+                Some("markdown") => Format::Markdown,
+                Some(format) => usage_error(&format!("unknown format {}", format)),
+                None => usage_error("--format expects one of json, text, markdown"),
+            },
+            "--merge-synonyms" => options.merge_synonyms = true,
+            "--categories" => options.categories = true,
+            "--scores" => options.scores = true,
+            "--offsets" => options.offsets = true,
+            flag if flag.starts_with("--") => usage_error(&format!("unknown option {}", flag)),
+            path => options.inputs.push(path.to_string()),
+        }
+    }
+
+    options
+}
+
+/* "-" is stdin */
+fn read_input(input: &str) -> io::Result<String> {
+    let mut text = String::new();
+
+    match input {
+        "-" => io::stdin().read_to_string(&mut text)?,
+        path => File::open(path)?.read_to_string(&mut text)?,
+    };
+
+    Ok(text)
+}
+
+/* the phrases of the result, annotated with their source when summarizing several documents */
+fn phrases<F>(result: &SummaryResult, annotate: F) -> Vec<String>
+    where F: Fn(&str, &str) -> String
+{
+    match result.sources {
+        Some(ref sources) => result.phrases
+            .iter()
+            .zip(sources.iter())
+            .map(|(phrase, source)| annotate(phrase, source))
+            .collect(),
+        None => result.phrases.clone(),
+    }
+}
+
+/* the keywords of the result, followed by the words merged into them */
+fn keywords<F>(result: &SummaryResult, quote: F) -> Vec<String>
+    where F: Fn(&str) -> String
+{
+    result.keywords
+        .iter()
+        .map(|keyword| match result.variants.as_ref().and_then(|variants| variants.get(keyword)) {
+            Some(variants) => format!("{} ({})", quote(keyword), variants.join(", ")),
+            None => quote(keyword),
+        })
+        .collect()
+}
+
+/* the largest categories, like "noun.substance 30%" */
+fn shares(categories: &[CategoryShare]) -> Vec<String> {
+    categories
+        .iter()
+        .take(CATEGORIES_SHOWN)
+        .map(|category| format!("{} {:.0}%", category.name, category.share * 100.0))
+        .collect()
+}
+
+fn render(result: &SummaryResult, format: &Format) -> String {
+    match *format {
+        Format::Json => serde_json::to_string_pretty(result)
+            .unwrap_or_else(|e| fail(&format!("could not serialize result: {}", e))),
+        Format::Text => {
+            let mut out = phrases(result, |phrase, source| format!("[{}] {}", source, phrase)).join("\n");
+
+            out.push_str(&format!("\n\nKeywords: {}", keywords(result, |keyword| keyword.to_string()).join(", ")));
+
+            if !result.keyphrases.is_empty() {
+                out.push_str(&format!("\nKeyphrases: {}", result.keyphrases.join(", ")));
+            }
+
+            if let Some(ref topics) = result.topics {
+                let topics = topics.iter().map(|topic| format!("{} ({:.2})", topic.label, topic.score)).collect::<Vec<String>>();
+                out.push_str(&format!("\nTopics: {}", topics.join(", ")));
+            }
+
+            if let Some(ref categories) = result.categories {
+                out.push_str(&format!("\nCategories: {}", shares(categories).join(", ")));
+            }
+
+            if let Some(ref titles) = result.titles {
+                out.push_str(&format!("\n\nTitles:\n{}", titles.join("\n")));
+            }
+
+            out
+        }
+        Format::Markdown => {
+            let mut out = String::from("## Summary\n\n");
+
+            for phrase in phrases(result, |phrase, source| format!("{} *({})*", phrase, source)) {
+                out.push_str(&format!("- {}\n", phrase));
+            }
+
+            out.push_str("\n## Keywords\n\n");
+            out.push_str(&keywords(result, |keyword| format!("`{}`", keyword)).join(", "));
+
+            if !result.keyphrases.is_empty() {
+                out.push_str("\n\n## Keyphrases\n\n");
+                out.push_str(&result.keyphrases
+                    .iter()
+                    .map(|keyphrase| format!("`{}`", keyphrase))
+                    .collect::<Vec<String>>()
+                    .join(", "));
+            }
+
+            if let Some(ref topics) = result.topics {
+                out.push_str("\n\n## Topics\n\n");
+                out.push_str(&topics
+                    .iter()
+                    .map(|topic| format!("- {} ({:.2}): {}", topic.label, topic.score, topic.keywords.join(", ")))
+                    .collect::<Vec<String>>()
+                    .join("\n"));
+            }
+
+            if let Some(ref categories) = result.categories {
+                out.push_str("\n\n## Categories\n\n");
+                out.push_str(&shares(categories)
+                    .iter()
+                    .map(|share| format!("- {}", share))
+                    .collect::<Vec<String>>()
+                    .join("\n"));
+            }
+
+            if let Some(ref titles) = result.titles {
+                out.push_str("\n\n## Titles\n\n");
+
+                for title in titles.iter() {
+                    out.push_str(&format!("- {}\n", title));
+                }
+            }
+
+            out.trim_right().to_string()
+        }
+    }
+}
+
+fn load_summary(dict: &Option<String>, idf: &Option<String>) -> Summary {
+    let mut builder = SummaryBuilder::new();
+
+    if let Some(ref path) = *dict {
+        builder = builder.dictionary(path);
+    }
+
+    if let Some(ref path) = *idf {
+        builder = builder.idf(path);
+    }
+
+    let summary = builder.build().unwrap_or_else(|e| fail(&format!("could not load dictionary or document frequencies: {}", e)));
+
+    for warning in summary.warnings() {
+        let _ = writeln!(io::stderr(), "summary: warning: {}", warning);
+    }
+
+    summary
+}
+
+/* summary idf CORPUS OUTPUT */
+fn build_idf(options: &Options) {
+    if options.inputs.len() != 2 {
+        usage_error("idf expects a corpus directory and an output file");
+    }
+
+    let (corpus, output) = (&options.inputs[0], &options.inputs[1]);
+
+    // the table is counted from scratch, --idf and $IDF_PATH are not read
+    let summary = load_summary(&options.dict, &None);
+
+    let (idf, warnings) = summary
+        .build_idf(corpus)
+        .unwrap_or_else(|e| fail(&format!("could not read corpus: {}", e)));
+
+    for warning in warnings {
+        let _ = writeln!(io::stderr(), "summary: warning: {}", warning);
+    }
+
+    idf.save(output).unwrap_or_else(|e| fail(&format!("could not write {}: {}", output, e)));
+
+    println!("counted {} documents", idf.documents());
+}
+
+/* summary define WORD... */
+fn define(options: &Options) {
+    if options.inputs.is_empty() {
+        usage_error("define expects a word");
+    }
+
+    let summary = load_summary(&options.dict, &None);
+    let stemmer = summary.stemmer();
+    let relations = [(Relation::Hypernym, "hypernyms"),
+                     (Relation::Antonym, "antonyms"),
+                     (Relation::SimilarTo, "similar to"),
+                     (Relation::Pertainym, "pertains to")];
+
+    for word in options.inputs.iter() {
+        let word = word.to_lowercase().replace(' ', "_");
+
+        for &(part, name) in [(NOUN, "noun"), (VERB, "verb"), (ADJ, "adj"), (ADV, "adv")].iter() {
+            // a dictionary may lack the data file of some part of speech, which
+            // should not hide the meanings found in the others
+            let synsets = match stemmer.synsets(part, &stemmer.lemma(part, &word)) {
+                Ok(synsets) => synsets,
+                Err(e) => {
+                    let _ = writeln!(io::stderr(), "summary: could not read {} synsets: {}", name, e);
+                    continue;
+                }
+            };
+
+            for synset in synsets {
+                println!("{} ({}) {}: {}", name, synset.lexicographer_file, synset.lemmas.join(", "), synset.gloss);
+
+                for &(ref relation, label) in relations.iter() {
+                    let related = synset
+                        .related(relation.clone())
+                        .iter()
+                        .map(|pointer| stemmer.synset(pointer.part, pointer.offset))
+                        .collect::<io::Result<Vec<_>>>()
+                        .unwrap_or_else(|e| fail(&format!("could not read related synsets: {}", e)));
+
+                    if !related.is_empty() {
+                        let lemmas = related.iter().map(|synset| synset.lemmas.join(", ")).collect::<Vec<String>>();
+                        println!("    {}: {}", label, lemmas.join("; "));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/* entry point for everything but the server; args exclude the program name */
+pub fn run(args: &[String]) {
+    let options = parse_options(&args[1..]);
+
+    match args[0].as_str() {
+        "summarize" => {}
+        "idf" => return build_idf(&options),
+        "define" => return define(&options),
+        "help" | "--help" | "-h" => {
+            print!("{}", USAGE);
+            return;
+        }
+        command => usage_error(&format!("unknown command {}", command)),
+    }
+
+    let mut texts = (if options.inputs.is_empty() { vec!["-".to_string()] } else { options.inputs.clone() })
+        .into_iter()
+        .map(|input| {
+            let text = read_input(&input)
+                .unwrap_or_else(|e| fail(&format!("could not read {}: {}", input, e)));
+
+            Document { id: Some(input), text }
+        })
+        .collect::<Vec<Document>>();
+
+    let (text, documents) = if texts.len() == 1 {
+        (texts.remove(0).text, None)
+    } else {
+        (String::new(), Some(texts))
+    };
+
+    let summary = load_summary(&options.dict, &options.idf);
+
+    let operation = SummaryOperation {
+        id: None,
+        text: text,
+        documents: documents,
+        num_phrases: options.phrases,
+        ratio: options.ratio,
+        max_words: options.max_words,
+        max_chars: options.max_chars,
+        order: options.order.clone(),
+        num_titles: Some(options.titles),
+        num_keywords: options.keywords,
+        num_keyphrases: options.keyphrases,
+        num_topics: Some(options.topics),
+        algorithm: options.algorithm.clone(),
+        normalization: options.normalization.clone(),
+        target_length: options.target_length,
+        lambda: options.lambda,
+        lead_bias: options.lead_bias,
+        paragraph_bias: options.paragraph_bias,
+        query: options.query.clone(),
+        merge_synonyms: Some(options.merge_synonyms),
+        include_categories: Some(options.categories),
+        include_scores: Some(options.scores),
+        include_offsets: Some(options.offsets),
+    };
+
+    let limits = Limits { max_text_length: usize::max_value() };
+
+    match summarize(&summary, &operation, &limits) {
+        Ok(result) => println!("{}", render(&result, &options.format)),
+        Err(e) => fail(&e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_options, render, Format};
+
+    use std::collections::BTreeMap;
+
+    use api::{CategoryShare, SummaryResult};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        let options = parse_options(&args(&["--phrases", "2", "--order", "score", "a.txt", "--lambda", "0.7",
+                                            "--merge-synonyms", "--format", "markdown", "-"]));
+
+        assert_eq!(options.inputs, vec!["a.txt", "-"]);
+        assert_eq!(options.phrases, Some(2));
+        assert_eq!(options.order, Some("score".to_string()));
+        assert_eq!(options.lambda, Some(0.7));
+        assert!(options.merge_synonyms && !options.categories);
+        assert!(match options.format { Format::Markdown => true, _ => false });
+
+        let defaults = parse_options(&[]);
+
+        assert!(defaults.inputs.is_empty() && defaults.phrases.is_none() && defaults.titles == 0);
+        assert!(match defaults.format { Format::Text => true, _ => false });
+    }
+
+    fn result() -> SummaryResult {
+        let mut variants = BTreeMap::new();
+        variants.insert("apple".to_string(), vec!["pome".to_string()]);
+
+        SummaryResult {
+            phrases: vec!["Red apples grow on tall trees.".to_string(), "The orchard needs water.".to_string()],
+            keywords: vec!["apple".to_string(), "tree".to_string()],
+            keyphrases: vec!["red apple".to_string()],
+            variants: Some(variants),
+            sources: Some(vec!["a.txt".to_string(), "b.txt".to_string()]),
+            titles: Some(vec!["Apple Tree".to_string()]),
+            topics: None,
+            categories: Some(vec![CategoryShare { name: "noun.plant".to_string(), share: 0.25 }]),
+            scores: None,
+            offsets: None,
+        }
+    }
+
+    #[test]
+    fn results_are_rendered_as_text() {
+        assert_eq!(render(&result(), &Format::Text),
+                   "[a.txt] Red apples grow on tall trees.\n\
+                    [b.txt] The orchard needs water.\n\
+                    \n\
+                    Keywords: apple (pome), tree\n\
+                    Keyphrases: red apple\n\
+                    Categories: noun.plant 25%\n\
+                    \n\
+                    Titles:\n\
+                    Apple Tree");
+    }
+
+    #[test]
+    fn results_are_rendered_as_markdown() {
+        assert_eq!(render(&result(), &Format::Markdown),
+                   "## Summary\n\n\
+                    - Red apples grow on tall trees. *(a.txt)*\n\
+                    - The orchard needs water. *(b.txt)*\n\
+                    \n\
+                    ## Keywords\n\n\
+                    `apple` (pome), `tree`\n\
+                    \n\
+                    ## Keyphrases\n\n\
+                    `red apple`\n\
+                    \n\
+                    ## Categories\n\n\
+                    - noun.plant 25%\n\
+                    \n\
+                    ## Titles\n\n\
+                    - Apple Tree");
+    }
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fmt;
+
+/// Everything that can go wrong while serving a summary.
+///
+/// Each error carries a stable machine readable [`code`](#method.code) and
+/// the HTTP [`status`](#method.status) it maps to.
+#[derive(Debug)]
+pub enum SummaryError {
+    // no text (or only whitespace) was submitted
+    EmptyText,
+    // the submitted text exceeds the configured limit (in bytes)
+    TextTooLarge { length: usize, limit: usize },
+    // titles were requested but the text yielded no noun keywords
+    NoKeywords,
+    // an option has a value that is not understood
+    InvalidOption { name: &'static str, value: String },
+    // options that exclude each other were given together
+    ConflictingOptions(Vec<&'static str>),
+    // several operations of a batch have the same id, given or positional
+    DuplicateId(String),
+    // topics were requested but the dictionary has neither noun synsets nor noun lexicographer files
+    TopicsUnavailable,
+    // anything that is not the client's fault
+    Internal(String),
+}
+
+impl SummaryError {
+    /// Stable, machine readable error code, e.g. `"empty_text"`.
+    pub fn code(&self) -> &'static str {
+        match *self {
+            SummaryError::EmptyText => "empty_text",
+            SummaryError::TextTooLarge { .. } => "text_too_large",
+            SummaryError::NoKeywords => "no_keywords",
+            SummaryError::InvalidOption { .. } => "invalid_option",
+            SummaryError::ConflictingOptions(_) => "conflicting_options",
+            SummaryError::DuplicateId(_) => "duplicate_id",
+            SummaryError::TopicsUnavailable => "topics_unavailable",
+            SummaryError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// HTTP status code: 400 for bad input, 413 for oversized input, 501
+    /// for what the dictionary cannot do and 500 for internal failures.
+    pub fn status(&self) -> u16 {
+        match *self {
+            SummaryError::EmptyText |
+            SummaryError::NoKeywords |
+            SummaryError::InvalidOption { .. } |
+            SummaryError::ConflictingOptions(_) |
+            SummaryError::DuplicateId(_) => 400,
+            SummaryError::TextTooLarge { .. } => 413,
+            SummaryError::TopicsUnavailable => 501,
+            SummaryError::Internal(_) => 500,
+        }
+    }
+}
+
+impl fmt::Display for SummaryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SummaryError::EmptyText =>
+                write!(f, "the text to summarize is empty"),
+            SummaryError::TextTooLarge { length, limit } =>
+                write!(f, "the text is {} bytes long, the limit is {} bytes", length, limit),
+            SummaryError::NoKeywords =>
+                write!(f, "titles were requested but the text contains no noun keywords"),
+            SummaryError::InvalidOption { name, ref value } =>
+                write!(f, "invalid value \"{}\" for {}", value, name),
+            SummaryError::ConflictingOptions(ref names) =>
+                write!(f, "only one of {} can be given", names.join(", ")),
+            SummaryError::DuplicateId(ref id) =>
+                write!(f, "the id \"{}\" is used by several operations of the batch", id),
+            SummaryError::TopicsUnavailable =>
+                write!(f, "topics were requested but the dictionary has no noun synsets (data.noun) or noun lexicographer files (dbfiles/noun.*)"),
+            SummaryError::Internal(ref reason) =>
+                write!(f, "internal error: {}", reason),
+        }
+    }
+}
+
+impl Error for SummaryError {
+    fn description(&self) -> &str {
+        match *self {
+            SummaryError::EmptyText => "empty text",
+            SummaryError::TextTooLarge { .. } => "text too large",
+            SummaryError::NoKeywords => "no keywords",
+            SummaryError::InvalidOption { .. } => "invalid option",
+            SummaryError::ConflictingOptions(_) => "conflicting options",
+            SummaryError::DuplicateId(_) => "duplicate id",
+            SummaryError::TopicsUnavailable => "topics unavailable",
+            SummaryError::Internal(_) => "internal error",
+        }
+    }
+}
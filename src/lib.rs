@@ -0,0 +1,40 @@
+//! Extractive text summarization backed by WordNet.
+//!
+//! ```no_run
+//! use summary::{titlegenerator, Summary};
+//!
+//! let summary = Summary::with_dictionary("./dict/").unwrap();
+//!
+//! let (phrases, keywords) = summary.summarize("Some long article. With many sentences.", 3);
+//! let titles = titlegenerator::build_titles(&keywords, 2);
+//! ```
+//!
+//! The HTTP server and the command line interface live in the `summary`
+//! binary, which is only built with the (default) `server` feature.
+
+extern crate rand;
+extern crate regex;
+
+mod summary;
+
+pub use summary::{Algorithm, Categories, Category, Digest, Idf, Keyphrase, Keyword, Length, Normalization, Order, Phrase, Summary, SummaryBuilder, SummaryOptions, Topic, DEFAULT_TARGET_LENGTH};
+
+/// Sentence splitter.
+pub use summary::katana;
+pub use summary::katana::Span;
+
+/// WordNet lemmatizer and part-of-speech lookup.
+pub use summary::wordnet_stemmer::{self, WordnetStemmer, NOUN, VERB, ADJ, ADV};
+
+/// WordNet synsets: definitions and relations between meanings.
+pub use summary::synset::{self, Pointer, Relation, Synset};
+
+/* General toolchain for misc. tasks */
+mod string_utils;
+
+/// Generates titles from keywords.
+pub mod titlegenerator;
+
+mod error;
+
+pub use error::SummaryError;
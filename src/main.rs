@@ -1,77 +1,188 @@
 #![feature(plugin)]
 #![plugin(rocket_codegen)]
 
-extern crate rand;
-extern crate regex;
+extern crate rayon;
 
 extern crate rocket;
+extern crate serde;
 extern crate serde_json;
 #[macro_use] extern crate rocket_contrib;
 #[macro_use] extern crate serde_derive;
 
-use rocket::State;
+extern crate summary;
+
+use rocket::{Request, Rocket, State};
 use rocket::response::content;
 
-use rocket_contrib::{JSON, Value};
+use rocket_contrib::JSON;
+
+use summary::{Summary, SummaryBuilder, SummaryError};
 
-mod summary;
+/* Request and response types shared by the server and the CLI */
+mod api;
 
-use summary::Summary;
+/* Command line interface */
+mod cli;
 
-/* General toolchain for misc. tasks */
-mod string_utils;
+use api::{ApiError, BatchItemResult, BatchResult, ErrorBody, Limits, SummaryOperation, batch_id, duplicate_ids, summarize, to_json};
 
-/* Generate titles */
-mod titlegenerator;
+use rayon::prelude::*;
 
-use std::sync::{Arc, Mutex};
+use std::env;
+use std::io::{self, Write};
+use std::process;
+
+#[post("/summary", format = "application/json", data = "<input>")]
+fn new(input: JSON<SummaryOperation>, summary: State<Summary>, limits: State<Limits>) -> Result<content::JSON<String>, ApiError> {
+    to_json(&summarize(summary.inner(), &input, limits.inner())?)
+}
 
-#[derive(Serialize, Deserialize)]
-struct SummaryOperation {
-    text: String,
-    num_phrases: Option<u32>,
-    num_titles: Option<u32>
+/*
+    Summarize many documents at once. Every operation is answered under its
+    id (or its position in the array if it has none); a failing operation
+    yields an error entry instead of failing the whole batch, as do
+    operations sharing an id.
+
+    The operations are summarized in parallel on the rayon thread pool.
+*/
+#[post("/summary/batch", format = "application/json", data = "<input>")]
+fn batch(input: JSON<Vec<SummaryOperation>>, summary: State<Summary>, limits: State<Limits>) -> Result<content::JSON<String>, ApiError> {
+    let (summary, limits) = (summary.inner(), limits.inner());
+    let duplicates = duplicate_ids(&input);
+
+    let items = input
+        .par_iter()
+        .enumerate()
+        .map(|(i, operation)| {
+            let id = batch_id(operation, i);
+
+            let result = if duplicates.contains(&id) {
+                Err(SummaryError::DuplicateId(id.clone()))
+            } else {
+                summarize(summary, operation, limits)
+            };
+
+            match result {
+                Ok(result) => (id, BatchItemResult { result: Some(result), error: None }),
+                Err(e) => (id, BatchItemResult { result: None, error: Some(ErrorBody::from(&e)) }),
+            }
+        })
+        .collect::<Vec<(String, BatchItemResult)>>();
+
+    to_json(&BatchResult { results: items.into_iter().collect() })
 }
 
-#[derive(Serialize)]
-struct SummaryResult {
-    phrases: Vec<String>,
-    keywords: Vec<String>,
+/*
+    Requests Rocket rejects before they reach a route, like malformed JSON
+    or a body that does not match SummaryOperation, are answered with the
+    same error body as summarize errors.
+*/
+#[error(400)]
+fn bad_request() -> ErrorBody {
+    ErrorBody::new("malformed_request", 400, "the request body is not valid JSON or does not match the expected fields".to_string())
+}
 
-    titles: Option<Vec<String>>
+#[error(404)]
+fn not_found(request: &Request) -> ErrorBody {
+    ErrorBody::new("not_found", 404,
+                   format!("no route for {} {}, POST application/json to /summary or /summary/batch", request.method(), request.uri()))
 }
 
-#[post("/summary", format = "application/json", data = "<input>")]
-fn new(input: JSON<SummaryOperation>, store: State<Arc<Mutex<Summary>>>) -> content::JSON<String> {
-    let text = &input.text;
-    let num_phrases = input.num_phrases.unwrap_or(3u32);
-    let num_titles = input.num_titles.unwrap_or(0u32);
-
-    let (phrases, keywords) = (*store.inner().lock().unwrap()).summarize(&text, num_phrases);
-
-    let titles = {
-        if num_titles == 0 {
-            None
-        } else {
-            Some(titlegenerator::build_titles(&keywords, num_titles))
+#[error(413)]
+fn payload_too_large() -> ErrorBody {
+    ErrorBody::new("request_too_large", 413, "the request body is too large".to_string())
+}
+
+#[error(500)]
+fn internal_error() -> ErrorBody {
+    ErrorBody::new("internal_error", 500, "internal error".to_string())
+}
+
+/* the routes and catchers of the server, answering from `summary` */
+fn server(summary: Summary, limits: Limits) -> Rocket {
+    rocket::ignite()
+        .mount("/", routes![new, batch])
+        .catch(errors![bad_request, not_found, payload_too_large, internal_error])
+        .manage(summary)
+        .manage(limits)
+}
+
+fn serve() {
+    let summary = match SummaryBuilder::from_env().build() {
+        Ok(summary) => summary,
+        Err(e) => {
+            let _ = writeln!(io::stderr(), "summary: could not load dictionary or document frequencies: {}", e);
+            process::exit(1)
         }
     };
 
-    content::JSON(
-        serde_json::to_string::<SummaryResult>(
-            &SummaryResult { phrases, keywords, titles }
-        ).unwrap_or("{}".to_string())
-    )
+    // a dictionary missing parts still serves, with fewer keywords or no topics
+    for warning in summary.warnings() {
+        let _ = writeln!(io::stderr(), "summary: warning: {}", warning);
+    }
+
+    let limits = Limits {
+        max_text_length: env::var("SUMMARY_MAX_TEXT_LENGTH")
+            .ok()
+            .and_then(|limit| limit.parse::<usize>().ok())
+            .unwrap_or(api::DEFAULT_MAX_TEXT_LENGTH),
+    };
+
+    server(summary, limits).launch();
 }
 
-fn main(){
-    let summary = Summary::new();
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    match args.first().map(|arg| arg.as_str()) {
+        None | Some("serve") => serve(),
+        Some(_) => cli::run(&args),
+    }
+}
 
-    let store = Arc::new(Mutex::new(summary));
+#[cfg(test)]
+mod test {
+    use super::server;
 
-    rocket::ignite()
-        .mount("/", routes![new])
-        //.catch(errors![not_found])
-        .manage(store)
-        .launch();
-}
\ No newline at end of file
+    use api::Limits;
+
+    use rocket::http::{ContentType, Status};
+    use rocket::http::Method::Post;
+    use rocket::testing::MockRequest;
+
+    use summary::Summary;
+
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const BODY: &'static str = r#"{"text": "Red apples grow on tall trees. Green pears grow on short trees. The orchard needs water.", "num_phrases": 2}"#;
+
+    /* both requests are answered from the one managed Summary, released into the route together */
+    #[test]
+    fn concurrent_requests_share_one_summary() {
+        let summary = Summary::with_dictionary("tests/fixtures/dict").unwrap();
+        let rocket = Arc::new(server(summary, Limits { max_text_length: 1000 }));
+        let barrier = Arc::new(Barrier::new(2));
+
+        let workers = (0..2)
+            .map(|_| {
+                let (rocket, barrier) = (rocket.clone(), barrier.clone());
+
+                thread::spawn(move || {
+                    let mut request = MockRequest::new(Post, "/summary").header(ContentType::JSON).body(BODY);
+
+                    barrier.wait();
+                    let mut response = request.dispatch_with(&rocket);
+
+                    (response.status(), response.body().and_then(|body| body.into_string()))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let responses = workers.into_iter().map(|worker| worker.join().unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(responses[0].0, Status::Ok);
+        assert!(responses[0].1.as_ref().map_or(false, |body| body.contains("\"phrases\"")));
+        assert_eq!(responses[0], responses[1]);
+    }
+}
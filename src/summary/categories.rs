@@ -0,0 +1,268 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::path::Path;
+
+use super::digest::Category;
+use super::synset::LEXICOGRAPHER_FILES;
+
+/// The lexicographer categories of WordNet nouns and verbs, like
+/// "noun.feeling" for "anxiety" or "verb.consumption" for "drink", read
+/// from the lexicographer source files WordNet is compiled from
+/// (`dict/dbfiles`).
+///
+/// Adjectives and adverbs are left out: their files ("adj.all",
+/// "adv.all", ...) say nothing about what a text is about.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Categories {
+    // per lemma the index of the category in LEXICOGRAPHER_FILES and the number of senses in it
+    senses: HashMap<String, Vec<(usize, u32)>>,
+}
+
+/*
+    The words and pointers of a synset line of a lexicographer file, the
+    part before the gloss split at whitespace and brackets.
+*/
+fn lexicographer_tokens(line: &str) -> Vec<&str> {
+    let line = line.trim_left_matches('[').trim_left();
+
+    if !line.starts_with('{') {
+        return Vec::new();
+    }
+
+    // the gloss follows the words and pointers
+    let head = match line.find(" (") {
+        Some(gloss) => &line[1..gloss],
+        None => &line[1..],
+    };
+
+    head.split(|c: char| c.is_whitespace() || c == '[' || c == ']')
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/* a word with its lexical id: "consume1" is ("consume", 1) */
+fn lexical_id(word: &str) -> (String, u32) {
+    let lemma = word.trim_right_matches(|c: char| c.is_digit(10));
+
+    (lemma.to_lowercase(), word[lemma.len()..].parse::<u32>().unwrap_or(0))
+}
+
+/*
+    The words of a synset line of a lexicographer file with their lexical
+    ids, which tell apart the synsets of a word in one file: "consume1,"
+    is ("consume", 1). Adjective markers are dropped.
+*/
+pub fn lexicographer_words(line: &str) -> Vec<(String, u32)> {
+    lexicographer_tokens(line)
+        .into_iter()
+        // pointers have their symbol after the comma, verb frames are numbers
+        .filter(|token| token.ends_with(',') && token.find(',') == Some(token.len() - 1))
+        .map(|token| lexical_id(token[..token.len() - 1].split('(').next().unwrap()))
+        .filter(|&(ref lemma, _)| !lemma.is_empty())
+        .collect()
+}
+
+/*
+    The pointers of a synset line of a lexicographer file: the number of
+    the file they point into (None for the file of the line itself), the
+    word they point to with its lexical id and their symbol, so
+    "noun.Tops:feeling,@" is (Some(3), ("feeling", 0), "@"). Pointers to
+    adjective satellites, like "adj.all:ill1^faint,+", point to the
+    satellite.
+*/
+pub fn lexicographer_pointers(line: &str) -> Vec<(Option<usize>, (String, u32), &str)> {
+    lexicographer_tokens(line)
+        .into_iter()
+        .filter_map(|token| {
+            let (target, symbol) = match token.find(',') {
+                Some(comma) if comma + 1 < token.len() => (&token[..comma], &token[comma + 1..]),
+                _ => return None,
+            };
+
+            let (file, word) = match target.find(':') {
+                Some(colon) => match LEXICOGRAPHER_FILES.iter().position(|&name| name == &target[..colon]) {
+                    Some(file) => (Some(file), &target[colon + 1..]),
+                    None => return None,
+                },
+                None => (None, target),
+            };
+
+            // verb frames, like "8,11", are no pointers
+            if word.chars().all(|c| c.is_digit(10)) {
+                return None;
+            }
+
+            Some((file, lexical_id(word.rsplit('^').next().unwrap()), symbol))
+        })
+        .collect()
+}
+
+/*
+    Calls `line` with the number of the file in LEXICOGRAPHER_FILES for
+    every line of the lexicographer files in `dbfiles` whose names start
+    with one of `prefixes`, file by file in that order. Files missing from
+    the directory are skipped.
+*/
+pub fn read_lexicographer_files<F>(dbfiles: &Path, prefixes: &[&str], mut line: F) -> io::Result<()>
+    where F: FnMut(usize, &str)
+{
+    for (number, name) in LEXICOGRAPHER_FILES.iter().enumerate() {
+        if !prefixes.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+
+        let file = match File::open(dbfiles.join(name)) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        let mut buffer = Vec::new();
+        let mut reader = BufReader::new(file);
+
+        // the files are Latin-1, but lemmas are ASCII
+        while reader.read_until(b'\n', &mut buffer)? > 0 {
+            line(number, &String::from_utf8_lossy(&buffer));
+            buffer.clear();
+        }
+    }
+
+    Ok(())
+}
+
+fn is_noun(category: usize) -> bool {
+    LEXICOGRAPHER_FILES[category].starts_with("noun.")
+}
+
+impl Categories {
+    /// No categories: every profile is empty.
+    pub fn new() -> Categories {
+        Categories::default()
+    }
+
+    /// Reads the noun and verb lexicographer files (`noun.feeling`,
+    /// `verb.body`, ...) in `dbfiles`; files missing from the directory
+    /// are skipped.
+    pub fn load<P: AsRef<Path>>(dbfiles: P) -> io::Result<Categories> {
+        let mut categories = Categories::new();
+
+        read_lexicographer_files(dbfiles.as_ref(), &["noun.", "verb."], |category, line| {
+            for (word, _) in lexicographer_words(line) {
+                let senses = categories.senses.entry(word).or_insert(Vec::new());
+
+                match senses.iter().position(|&(other, _)| other == category) {
+                    Some(i) => senses[i].1 = senses[i].1 + 1,
+                    None => senses.push((category, 1)),
+                }
+            }
+        })?;
+
+        Ok(categories)
+    }
+
+    /// Whether no lexicographer files were read.
+    pub fn is_empty(&self) -> bool {
+        self.senses.is_empty()
+    }
+
+    /// The categories of `lemma` (like "blood_pressure") with its number of
+    /// senses in each.
+    pub fn of(&self, lemma: &str) -> Vec<(&'static str, u32)> {
+        self.senses
+            .get(lemma)
+            .map(|senses| senses.iter().map(|&(category, count)| (LEXICOGRAPHER_FILES[category], count)).collect())
+            .unwrap_or(Vec::new())
+    }
+
+    /// Share of every category among `lemmas` that have one, largest first.
+    ///
+    /// Lemmas that can be nouns count for their noun categories only,
+    /// others for their verb categories. Lemmas in several
+    /// categories count for each in proportion to their number of senses
+    /// in it.
+    pub fn profile<I, S>(&self, lemmas: I) -> Vec<Category>
+        where I: IntoIterator<Item = S>,
+              S: AsRef<str>
+    {
+        let mut weights = vec![0.0f64; LEXICOGRAPHER_FILES.len()];
+        let mut total = 0.0;
+
+        for lemma in lemmas {
+            if let Some(senses) = self.senses.get(lemma.as_ref()) {
+                let noun = senses.iter().any(|&(category, _)| is_noun(category));
+                let senses = senses.iter().filter(|&&(category, _)| is_noun(category) == noun).collect::<Vec<_>>();
+                let count = senses.iter().map(|&&(_, count)| count).sum::<u32>() as f64;
+
+                for &&(category, senses) in senses.iter() {
+                    weights[category] += senses as f64 / count;
+                }
+
+                total = total + 1.0;
+            }
+        }
+
+        let mut profile = weights
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, weight)| weight > 0.0)
+            .map(|(category, weight)| Category { name: LEXICOGRAPHER_FILES[category], share: weight / total })
+            .collect::<Vec<Category>>();
+
+        profile.sort_by(|a, b| {
+            b.share.partial_cmp(&a.share).unwrap_or(Ordering::Equal)
+                .then(a.name.cmp(b.name))
+        });
+
+        profile
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{lexicographer_pointers, lexicographer_words, Categories};
+
+    #[test]
+    fn words_are_read_without_pointers_or_markers() {
+        let words = |line| lexicographer_words(line).into_iter().map(|(word, _)| word).collect::<Vec<String>>();
+
+        assert_eq!(lexicographer_words("{ [ consume1, adj.all:consumptive,+ ] eat_up3, [eat13, frames: 22] \
+                                        verb.possession:spend,@ frames: 8,11 (use up (resources or materials)) }"),
+                   vec![("consume".to_string(), 1), ("eat_up".to_string(), 3), ("eat".to_string(), 13)]);
+        assert_eq!(words("[{ [ ABLE, noun.cognition:ability,+ UNABLE,!] (having the necessary means) }"), vec!["able"]);
+        assert_eq!(words("{ outback(a), remote, (inaccessible and sparsely populated) }"), vec!["outback", "remote"]);
+        assert!(words("(noun.feeling)").is_empty());
+    }
+
+    #[test]
+    fn pointers_are_read_with_their_file() {
+        let pointers = lexicographer_pointers("{ [ cell, adj.pert:cellular,+ ] living_thing,@ organism1,#p \
+                                               noun.cognition:biology,;c adj.all:ill1^faint,+ ((biology) the basic unit) }");
+
+        assert_eq!(pointers, vec![
+            (Some(1), ("cellular".to_string(), 0), "+"),
+            (None, ("living_thing".to_string(), 0), "@"),
+            (None, ("organism".to_string(), 1), "#p"),
+            (Some(9), ("biology".to_string(), 0), ";c"),
+            (Some(0), ("faint".to_string(), 0), "+"),
+        ]);
+        assert_eq!(lexicographer_pointers("{ [ consume1, adj.all:consumptive,+ ] eat_up3, verb.possession:spend,@ frames: 8,11 (use up) }"),
+                   vec![(Some(0), ("consumptive".to_string(), 0), "+"), (Some(40), ("spend".to_string(), 0), "@")]);
+        assert!(lexicographer_pointers("{ cell, (a unit) }").is_empty());
+    }
+
+    #[test]
+    fn lemmas_count_for_each_of_their_categories() {
+        // anxiety is in noun.feeling and noun.state, fear in noun.feeling and verb.emotion
+        let categories = Categories::load("tests/fixtures/categories-dbfiles").unwrap();
+        let profile = categories.profile(vec!["anxiety", "fear", "unknown", "anxiousness", "anxious", "worry"]);
+
+        assert_eq!(categories.of("anxiety"), vec![("noun.feeling", 1), ("noun.state", 1)]);
+        assert!(categories.of("anxious").is_empty());
+
+        // "fear" can be a noun, so it only counts as one
+        let shares = profile.iter().map(|category| (category.name, category.share)).collect::<Vec<_>>();
+        assert_eq!(shares, vec![("noun.feeling", 1.5 / 4.0), ("noun.state", 1.5 / 4.0), ("verb.emotion", 1.0 / 4.0)]);
+    }
+}
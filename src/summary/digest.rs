@@ -0,0 +1,107 @@
+use super::katana::Span;
+
+/// A phrase selected for the summary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Phrase {
+    /// The phrase as it appears in the text.
+    pub text: String,
+    /// Index of the text the phrase is from, always 0 unless several texts
+    /// are summarized together.
+    pub document: usize,
+    /// Position of the phrase among all phrases of its text, starting at 0.
+    pub index: usize,
+    /// Weight the phrase was ranked by, see
+    /// [`Algorithm`](enum.Algorithm.html).
+    pub score: f64,
+    /// Where the phrase is in the text, an empty span if the sentence
+    /// splitter could not locate it.
+    pub span: Span,
+}
+
+/// A keyword of the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyword {
+    /// The lemmatized word, or a collocation WordNet knows, like "blood
+    /// pressure", matched as a single word.
+    pub word: String,
+    /// Number of occurrences in the text.
+    pub frequency: u32,
+    /// Salience the keyword was ranked by: its frequency weighted by word
+    /// type, by how early in the text it first occurs and, if configured,
+    /// by its [inverse document frequency](struct.Idf.html).
+    pub score: f64,
+    /// Where the keyword occurs, in document order: the index of the text
+    /// (always 0 unless several texts are summarized together) and the
+    /// position in that text, empty for occurrences in phrases that could
+    /// not be located.
+    pub occurrences: Vec<(usize, Span)>,
+    /// Synonyms and related words of the text counted as this keyword, in
+    /// order of first occurrence, when
+    /// [`SummaryOptions::merge_synonyms`](struct.SummaryOptions.html#structfield.merge_synonyms)
+    /// is set.
+    pub variants: Vec<String>,
+
+    /// Parts of speech the word can take according to WordNet.
+    pub noun: bool,
+    pub verb: bool,
+    pub adj: bool,
+    pub adv: bool,
+}
+
+/// A keyphrase of the text: words that occur together, like "serotonin
+/// syndrome".
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyphrase {
+    /// The lemmatized words, separated by spaces.
+    pub phrase: String,
+    /// Number of occurrences in the text.
+    pub frequency: u32,
+    /// RAKE score the keyphrase was ranked by: the sum over its words of
+    /// how many words, on average, the candidate phrases containing the
+    /// word have.
+    pub score: f64,
+    /// Where the keyphrase occurs, like
+    /// [`Keyword::occurrences`](struct.Keyword.html#structfield.occurrences).
+    pub occurrences: Vec<(usize, Span)>,
+}
+
+/// A broad concept several keywords of the text are kinds of, like "drug"
+/// for "caffeine" and "nicotine", found by following WordNet hypernyms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Topic {
+    /// The concept, like "body part".
+    pub label: String,
+    /// Share of the keyword salience supporting the topic, between 0 and
+    /// 1: keywords count fully for their direct hypernyms and half as
+    /// much for every step further up.
+    pub score: f64,
+    /// The keywords that are kinds of the concept.
+    pub keywords: Vec<String>,
+}
+
+/// Share of the words of a text in a lexicographer category, like
+/// "noun.feeling" or "noun.substance".
+#[derive(Clone, Debug, PartialEq)]
+pub struct Category {
+    /// The lexicographer file, see
+    /// [`LEXICOGRAPHER_FILES`](synset/constant.LEXICOGRAPHER_FILES.html).
+    pub name: &'static str,
+    /// Between 0 and 1, the shares of all categories add up to 1. Words
+    /// in several categories count for each in proportion to their senses
+    /// in it.
+    pub share: f64,
+}
+
+/// Result of [`Summary::digest`](struct.Summary.html#method.digest) and
+/// [`Summary::digest_documents`](struct.Summary.html#method.digest_documents):
+/// the selected phrases, the keywords, the keyphrases and the topics, most
+/// salient first, all with the numbers they were ranked by, and the
+/// categories of the words, largest first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Digest {
+    pub phrases: Vec<Phrase>,
+    pub keywords: Vec<Keyword>,
+    pub keyphrases: Vec<Keyphrase>,
+    pub topics: Vec<Topic>,
+    pub categories: Vec<Category>,
+}
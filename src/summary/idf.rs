@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+/// Document frequencies of lemmas over a corpus, for weighting words by
+/// inverse document frequency (IDF): words found in most documents of the
+/// corpus weigh less than rare ones.
+///
+/// Built with [`Summary::build_idf`](struct.Summary.html#method.build_idf)
+/// and stored as a text file of the form
+///
+/// ```text
+/// documents 1200
+/// dose 1011
+/// serotonin 87
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Idf {
+    documents: u32,
+    // sorted, so saved tables are stable
+    document_frequency: BTreeMap<String, u32>,
+}
+
+fn invalid_data(path: &Path, line: usize, reason: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData,
+                   format!("{} line {}: {}", path.display(), line + 1, reason))
+}
+
+impl Idf {
+    /// An empty table: every word weighs the same.
+    pub fn new() -> Idf {
+        Idf::default()
+    }
+
+    /// Counts a document given by its lemmas; repeated lemmas count once.
+    pub fn add_document<I, S>(&mut self, lemmas: I)
+        where I: IntoIterator<Item = S>,
+              S: AsRef<str>
+    {
+        let lemmas = lemmas
+            .into_iter()
+            .map(|lemma| lemma.as_ref().to_string())
+            .collect::<HashSet<String>>();
+
+        for lemma in lemmas {
+            *self.document_frequency.entry(lemma).or_insert(0) += 1;
+        }
+
+        self.documents = self.documents + 1;
+    }
+
+    /// Number of documents counted.
+    pub fn documents(&self) -> u32 {
+        self.documents
+    }
+
+    /// Number of documents containing `lemma`.
+    pub fn document_frequency(&self, lemma: &str) -> u32 {
+        self.document_frequency.get(lemma).cloned().unwrap_or(0)
+    }
+
+    /// Smoothed inverse document frequency `ln((N + 1) / (df + 1)) + 1`,
+    /// from 1 for lemmas found in every document up to `ln(N + 1) + 1` for
+    /// lemmas found in none. Always 1 for an empty table.
+    pub fn weight(&self, lemma: &str) -> f64 {
+        if self.documents == 0 {
+            return 1.0;
+        }
+
+        let documents = self.documents as f64 + 1.0;
+        let frequency = self.document_frequency(lemma) as f64 + 1.0;
+
+        (documents / frequency).ln() + 1.0
+    }
+
+    /// Reads a table saved by [`save`](#method.save).
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Idf> {
+        let path = path.as_ref();
+
+        Idf::read(BufReader::new(File::open(path)?), path)
+    }
+
+    /* a saved table read from `reader`, errors say it is `path` */
+    fn read<R: BufRead>(reader: R, path: &Path) -> io::Result<Idf> {
+        let mut idf = Idf::new();
+
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let mut fields = line.split(' ');
+
+            let (name, count) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(name), Some(count), None) => (name, count),
+                _ => return Err(invalid_data(path, i, "expected a word and a count")),
+            };
+
+            let count = count
+                .parse::<u32>()
+                .map_err(|_| invalid_data(path, i, "the count is not a number"))?;
+
+            if i == 0 {
+                if name != "documents" {
+                    return Err(invalid_data(path, i, "expected the number of documents"));
+                }
+
+                idf.documents = count;
+            } else {
+                idf.document_frequency.insert(name.to_string(), count);
+            }
+        }
+
+        Ok(idf)
+    }
+
+    /// Writes the table to `path`, see [`load`](#method.load).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+
+        self.write(&mut out)?;
+        out.flush()
+    }
+
+    fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "documents {}", self.documents)?;
+
+        for (lemma, frequency) in self.document_frequency.iter() {
+            writeln!(out, "{} {}", lemma, frequency)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Idf;
+
+    use std::path::Path;
+
+    #[test]
+    fn common_words_weigh_less() {
+        let mut idf = Idf::new();
+
+        idf.add_document(vec!["dose", "effect", "serotonin", "dose"]);
+        idf.add_document(vec!["dose", "effect"]);
+        idf.add_document(vec!["dose", "tolerance"]);
+
+        assert_eq!(idf.documents(), 3);
+        assert_eq!(idf.document_frequency("dose"), 3);
+        assert_eq!(idf.weight("dose"), 1.0);
+        assert!(idf.weight("effect") > idf.weight("dose"));
+        assert!(idf.weight("serotonin") > idf.weight("effect"));
+        assert!(idf.weight("unseen") > idf.weight("serotonin"));
+        assert_eq!(Idf::new().weight("dose"), 1.0);
+    }
+
+    #[test]
+    fn tables_survive_a_round_trip() {
+        let mut idf = Idf::new();
+
+        idf.add_document(vec!["dose", "effect"]);
+        idf.add_document(vec!["dose"]);
+
+        let mut saved = Vec::new();
+        idf.write(&mut saved).unwrap();
+
+        assert_eq!(String::from_utf8(saved.clone()).unwrap(), "documents 2\ndose 2\neffect 1\n");
+        assert_eq!(Idf::read(&saved[..], Path::new("saved")).unwrap(), idf);
+    }
+}
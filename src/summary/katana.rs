@@ -1,5 +1,88 @@
 use regex::{Regex, Captures};
 
+/// Byte offsets `start..end` of a part of the original text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/* how far past the expected position a word may be found (in bytes) */
+const SPAN_SLACK: usize = 16;
+
+/// Like [`cut`](fn.cut.html), but also returns where each sentence starts
+/// and ends in `origin_text`.
+///
+/// The span covers the sentence from its first to its last character as
+/// they appear in `origin_text`, even where the returned sentence text was
+/// normalized while splitting. Sentences none of whose words can be found
+/// get an empty span.
+pub fn cut_with_spans(origin_text: &str) -> Vec<(String, Span)> {
+    let mut pos = 0;
+
+    cut(&origin_text.to_string())
+        .into_iter()
+        .map(|sentence| {
+            let span = locate(origin_text, &sentence, pos);
+            pos = span.end;
+
+            (sentence, span)
+        })
+        .collect()
+}
+
+/*
+    Find `sentence` in `text` at or after `from`.
+
+    Sentences are not necessarily verbatim substrings of the text (the
+    splitter inserts spaces and rewrites some quotes), so every word is
+    searched for close to where the previous one ended. Words that cannot
+    be found there are skipped, widening the window for the next one.
+*/
+fn locate(text: &str, sentence: &str, from: usize) -> Span {
+    let mut pos = from;
+    let mut start = None;
+    let mut slack = SPAN_SLACK;
+
+    for word in sentence.split_whitespace() {
+        let window_start = pos + text[pos..].len() - text[pos..].trim_left().len();
+
+        let mut window_end = (window_start + word.len() + slack).min(text.len());
+        while !text.is_char_boundary(window_end) {
+            window_end += 1;
+        }
+
+        match text[window_start..window_end].find(word) {
+            Some(offset) => {
+                if start.is_none() {
+                    start = Some(window_start + offset);
+                }
+
+                pos = window_start + offset + word.len();
+                slack = SPAN_SLACK;
+            }
+            None => slack += word.len() + 1,
+        }
+    }
+
+    Span { start: start.unwrap_or(from), end: pos }
+}
+
+/// Splits a text into sentences.
+///
+/// Abbreviations, initials, titles, decimal numbers, suspension points and
+/// sentence enders inside quotes or parentheses do not end a sentence. The
+/// returned sentences are trimmed.
 pub fn cut(origin_text: &String) -> Vec<String> {
     // Remove composite abbreviations.
     let composite = Regex::new(r"(?P<comp>et al\.)(?:\.)").unwrap();
@@ -156,3 +239,34 @@ pub fn cut(origin_text: &String) -> Vec<String> {
 
     results.to_owned()
 }
+
+#[cfg(test)]
+mod test {
+    use super::{cut, cut_with_spans};
+
+    #[test]
+    fn spans_cover_the_original_sentences() {
+        let text = "Dr. Farmer says it's 3.5 km deep.  \n\n\"Is it salty?\" he asks.The answer (probably) is yes!";
+
+        let sentences = cut_with_spans(text);
+
+        assert_eq!(sentences.iter().map(|&(ref sentence, _)| sentence.clone()).collect::<Vec<String>>(),
+                   cut(&text.to_string()));
+
+        assert_eq!(sentences.iter().map(|&(_, span)| &text[span.start..span.end]).collect::<Vec<&str>>(),
+                   vec!["Dr. Farmer says it's 3.5 km deep.",
+                        "\"Is it salty?\"",
+                        "he asks.",
+                        "The answer (probably) is yes!"]);
+    }
+
+    #[test]
+    fn spans_are_byte_offsets() {
+        let text = "Über alles. Ça va?";
+
+        let spans = cut_with_spans(text).into_iter().map(|(_, span)| span).collect::<Vec<_>>();
+
+        assert_eq!(&text[spans[1].start..spans[1].end], "Ça va?");
+        assert_eq!(spans[1].start, "Über alles. ".len());
+    }
+}
@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use super::digest::Keyphrase;
+use super::katana::Span;
+
+/* a run of adjacent words that may be a keyphrase */
+pub struct Candidate<'a> {
+    pub lemmas: Vec<&'a str>,
+    pub document: usize,
+    pub span: Span,
+}
+
+/*
+    RAKE (Rose et al., 2010): every word of the candidates is scored by
+
+        degree / frequency
+
+    where frequency is the number of candidates the word occurs in and
+    degree the total number of words of those candidates, so words that
+    mostly occur in longer phrases score higher. A candidate scores the sum
+    of its word scores.
+
+    Only candidates of at least two words are returned, highest score
+    first, then by first occurrence, then alphabetically.
+*/
+pub fn rake(candidates: &[Candidate]) -> Vec<Keyphrase> {
+    let mut frequency: HashMap<&str, usize> = HashMap::new();
+    let mut degree: HashMap<&str, usize> = HashMap::new();
+
+    for candidate in candidates.iter() {
+        for lemma in candidate.lemmas.iter() {
+            *frequency.entry(lemma).or_insert(0) += 1;
+            *degree.entry(lemma).or_insert(0) += candidate.lemmas.len();
+        }
+    }
+
+    let word_score = |lemma: &str| degree[lemma] as f64 / frequency[lemma] as f64;
+
+    /* group the occurrences of every phrase, remembering the first one */
+    let mut phrases: HashMap<String, (usize, Keyphrase)> = HashMap::new();
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        if candidate.lemmas.len() < 2 {
+            continue;
+        }
+
+        let phrase = candidate.lemmas.join(" ").replace('_', " ");
+
+        let &mut (_, ref mut keyphrase) = phrases.entry(phrase.clone()).or_insert((i, Keyphrase {
+            phrase,
+            frequency: 0,
+            score: candidate.lemmas.iter().map(|lemma| word_score(lemma)).sum(),
+            occurrences: Vec::new(),
+        }));
+
+        keyphrase.frequency = keyphrase.frequency + 1;
+        keyphrase.occurrences.push((candidate.document, candidate.span));
+    }
+
+    let mut ranked = phrases.into_iter().map(|(_, ranked)| ranked).collect::<Vec<(usize, Keyphrase)>>();
+
+    ranked.sort_by(|&(a_first, ref a), &(b_first, ref b)| {
+        b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+            .then(a_first.cmp(&b_first))
+            .then(a.phrase.cmp(&b.phrase))
+    });
+
+    ranked.into_iter().map(|(_, keyphrase)| keyphrase).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{rake, Candidate};
+    use super::super::katana::Span;
+
+    fn candidate(lemmas: &[&'static str], start: usize) -> Candidate<'static> {
+        Candidate { lemmas: lemmas.to_vec(), document: 0, span: Span { start, end: start + 1 } }
+    }
+
+    #[test]
+    fn longer_phrases_score_higher() {
+        let candidates = vec![
+            candidate(&["serotonin", "syndrome"], 0),
+            candidate(&["serotonin"], 10),
+            candidate(&["severe", "serotonin", "syndrome"], 20),
+            candidate(&["dose"], 30),
+            candidate(&["serotonin", "syndrome"], 40),
+        ];
+
+        let keyphrases = rake(&candidates);
+        let phrases = keyphrases.iter().map(|keyphrase| keyphrase.phrase.as_str()).collect::<Vec<&str>>();
+
+        assert_eq!(phrases, vec!["severe serotonin syndrome", "serotonin syndrome"]);
+        assert_eq!(keyphrases[1].frequency, 2);
+        assert_eq!(keyphrases[1].occurrences.len(), 2);
+        assert!(keyphrases[0].score > keyphrases[1].score);
+    }
+}
@@ -1,35 +1,358 @@
 /* phrase cutter */
-mod katana;
+pub mod katana;
 
 /* Stemming & word type detection */
-mod wordnet_stemmer;
+pub mod wordnet_stemmer;
 
+/* Definitions and relations of WordNet synsets */
+pub mod synset;
+
+/* Scored summary results */
+mod digest;
+
+/* Knobs for Summary::digest */
+mod options;
+
+/* Graph based phrase ranking */
+mod textrank;
+
+/* Picking phrases from their weights */
+mod selection;
+
+/* Corpus wide word weights */
+mod idf;
+
+/* Multi-word keyphrases */
+mod keyphrases;
+
+/* Counting synonyms as one word */
+mod synonyms;
+
+/* Broad concepts from hypernyms of the keywords */
+mod topics;
+
+/* Lexicographer categories of the words of a text */
+mod categories;
+
+pub use self::categories::Categories;
+pub use self::digest::{Category, Digest, Keyphrase, Keyword, Phrase, Topic};
+pub use self::idf::Idf;
+pub use self::options::{Algorithm, Length, Normalization, Order, SummaryOptions, DEFAULT_TARGET_LENGTH};
+
+use self::katana::Span;
+use self::keyphrases::Candidate;
+use self::selection::{Budget, Cost};
+use self::synonyms::Lemma;
+use self::synset::Relation;
+use self::topics::{Concept, Sense};
 use self::wordnet_stemmer::{WordnetStemmer, NOUN, VERB, ADJ, ADV};
 
 use std::env;
-use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/* longer runs of words are not considered keyphrases */
+const MAX_KEYPHRASE_WORDS: usize = 4;
+
+/* topics are found from this many of the best keywords */
+const TOPIC_KEYWORDS: usize = 10;
+
+/* keywords first seen at the start of a text weigh up to this much more */
+const KEYWORD_POSITION_BONUS: f64 = 0.5;
+
+/*
+    phrases containing all terms of the query weigh 1 + QUERY_BOOST times
+    as much, phrases containing some of them proportionally less
+*/
+const QUERY_BOOST: f64 = 9.0;
 
 const STOP_WORDS: &'static [&'static str] = &["a", "able", "about", "across", "after", "all", "almost", "also", "am", "among", "an", "and", "any", "are", "as", "at", "be", "because", "been", "but", "by", "can", "cannot", "could", "dear", "did", "do", "does", "either", "else", "ever", "every", "for", "from", "get", "got", "had", "has", "have", "he", "her", "hers", "him", "his", "how", "however", "i", "if", "in", "into", "is", "it", "its", "just", "least", "let", "like", "likely", "may", "me", "might", "most", "must", "my", "neither", "no", "nor", "not", "of", "off", "often", "on", "only", "or", "other", "our", "own", "rather", "said", "say", "says", "she", "should", "since", "so", "some", "than", "that", "the", "their", "them", "then", "there", "these", "they", "this", "tis", "to", "too", "twas", "us", "wants", "was", "we", "were", "what", "when", "where", "which", "while", "who", "whom", "why", "will", "with", "would", "yet", "you", "your"];
 
+/* a word of a sentence that survived stop word filtering */
+struct Token {
+    lemma: String,
+    span: Span,
+}
+
+struct Sentence {
+    text: String,
+    // in the document the sentence is from
+    span: Span,
+    tokens: Vec<Token>,
+    // index of the document and of the sentence in that document
+    document: usize,
+    position: usize,
+}
+
+/* whitespace separated words of `text` with their byte offsets */
+fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            if let Some(start) = start.take() {
+                words.push((start, &text[start..i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+
+    if let Some(start) = start {
+        words.push((start, &text[start..]));
+    }
+
+    words
+}
+
+/* `e`, saying which file it is about */
+fn path_error(path: &Path, e: io::Error) -> io::Error {
+    io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+}
+
+/* whether the ith sentence starts a paragraph, i.e. starts its document or a line break precedes it */
+fn paragraph_start(documents: &[&str], sentences: &[Sentence], i: usize) -> bool {
+    let sentence = &sentences[i];
+
+    if sentence.position == 0 {
+        return true;
+    }
+
+    if sentence.span.is_empty() {
+        return false;
+    }
+
+    // the closest preceding sentence of the document that could be located
+    let previous_end = sentences[..i]
+        .iter()
+        .rev()
+        .take_while(|previous| previous.document == sentence.document)
+        .find(|previous| !previous.span.is_empty())
+        .map(|previous| previous.span.end)
+        .unwrap_or(0);
+
+    documents[sentence.document][previous_end..sentence.span.start].contains('\n')
+}
+
+/// Configures and loads a [`Summary`](struct.Summary.html).
+///
+/// ```no_run
+/// use summary::SummaryBuilder;
+///
+/// let summary = SummaryBuilder::new()
+///     .dictionary("/usr/share/wordnet/")
+///     .build()
+///     .unwrap();
+/// ```
+pub struct SummaryBuilder {
+    dict_path: String,
+    idf_path: Option<String>,
+    stop_words: HashSet<String>,
+}
+
+impl SummaryBuilder {
+    /// Starts from the defaults: the dictionary at `./dict/`, no document
+    /// frequencies and the built-in English stop words. The environment is
+    /// not read, see [`from_env`](#method.from_env).
+    pub fn new() -> SummaryBuilder {
+        SummaryBuilder {
+            dict_path: "./dict/".to_string(),
+            idf_path: None,
+            stop_words: STOP_WORDS
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+        }
+    }
+
+    /// Like [`new`](#method.new), but with the dictionary at
+    /// `$WORDNET_PATH` and the document frequencies at `$IDF_PATH` if they
+    /// are set.
+    pub fn from_env() -> SummaryBuilder {
+        SummaryBuilder::from_vars(|name| env::var(name).ok())
+    }
+
+    /* from_env, with the variables looked up by `var` */
+    fn from_vars<F: Fn(&str) -> Option<String>>(var: F) -> SummaryBuilder {
+        let builder = SummaryBuilder::new();
+
+        let builder = match var("WORDNET_PATH") {
+            Some(dict_path) => builder.dictionary(&dict_path),
+            None => builder,
+        };
+
+        match var("IDF_PATH") {
+            Some(idf_path) => builder.idf(&idf_path),
+            None => builder,
+        }
+    }
+
+    /// Directory containing the WordNet `index.*` and `*.exc` files and,
+    /// optionally, the lexicographer files in `dbfiles/`, which
+    /// [`Digest::categories`](struct.Digest.html#structfield.categories)
+    /// are read from.
+    pub fn dictionary(mut self, dict_path: &str) -> SummaryBuilder {
+        self.dict_path = dict_path.to_string();
+        self
+    }
+
+    /// Document frequencies saved by [`Idf::save`](struct.Idf.html#method.save);
+    /// words are then weighted by TF-IDF instead of by their frequency in
+    /// the text alone.
+    pub fn idf(mut self, idf_path: &str) -> SummaryBuilder {
+        self.idf_path = Some(idf_path.to_string());
+        self
+    }
+
+    /// Replaces the stop words, i.e. the words ignored when weighting phrases.
+    pub fn stop_words<I, S>(mut self, stop_words: I) -> SummaryBuilder
+        where I: IntoIterator<Item = S>,
+              S: AsRef<str>
+    {
+        self.stop_words = stop_words
+            .into_iter()
+            .map(|word| word.as_ref().to_lowercase())
+            .collect();
+        self
+    }
+
+    /// Loads the dictionary and the document frequencies; fails if any of
+    /// their files cannot be read, or if the dictionary has no words at
+    /// all. Missing parts of the dictionary are listed in
+    /// [`Summary::warnings`](struct.Summary.html#method.warnings).
+    pub fn build(self) -> io::Result<Summary> {
+        let stemmer = WordnetStemmer::new(&self.dict_path)?;
+
+        // without lexicographer files every document is uncategorized
+        let dbfiles = Path::new(&self.dict_path).join("dbfiles");
+        let categories = if dbfiles.is_dir() {
+            Categories::load(&dbfiles)?
+        } else {
+            Categories::new()
+        };
+
+        let idf = match self.idf_path {
+            Some(ref idf_path) => Idf::load(idf_path)?,
+            None => Idf::new(),
+        };
+
+        Ok(Summary {
+            stemmer,
+            categories,
+            idf,
+            stop_words: self.stop_words,
+        })
+    }
+}
+
+/// Extractive summarizer: picks the most representative phrases and the
+/// noun keywords of a text.
 pub struct Summary {
     stemmer: WordnetStemmer,
+    categories: Categories,
+    idf: Idf,
     stop_words: HashSet<String>,
 }
 
 impl Summary {
+    /// Loads the configuration from the environment, see
+    /// [`SummaryBuilder::from_env`].
+    ///
+    /// # Panics
+    ///
+    /// If the dictionary cannot be loaded.
+    ///
+    /// [`SummaryBuilder::from_env`]: struct.SummaryBuilder.html#method.from_env
     pub fn new() -> Summary {
-        let dict_path = &env::var("WORDNET_PATH").unwrap_or("./dict/".to_string());
-        let stemmer = WordnetStemmer::new(dict_path).unwrap();
+        SummaryBuilder::from_env().build().unwrap()
+    }
 
-        let stop_words = STOP_WORDS
-            .iter()
-            .map(|word| word.to_string())
-            .collect::<HashSet<String>>();
+    /// Loads the dictionary at `dict_path` with the default stop words and
+    /// no document frequencies, whatever the environment says.
+    pub fn with_dictionary(dict_path: &str) -> io::Result<Summary> {
+        SummaryBuilder::new().dictionary(dict_path).build()
+    }
 
-        Summary {
-            stemmer,
-            stop_words,
+    /// The stemmer used to lemmatize and classify words.
+    pub fn stemmer(&self) -> &WordnetStemmer {
+        &self.stemmer
+    }
+
+    /// What the dictionary lacks, like "index.noun is missing, compiled it
+    /// from dbfiles/noun.*: the synsets of nouns are unavailable". Parts of
+    /// speech without an index file are compiled from the lexicographer
+    /// files in `dbfiles/` or, without those, have no words at all.
+    pub fn warnings(&self) -> &[String] {
+        &self.stemmer.warnings
+    }
+
+    /// Whether [topics](struct.Topic.html) can be looked up: they need the
+    /// noun synsets of the dictionary (`data.noun`) or, without them, the
+    /// noun lexicographer files (`dbfiles/noun.*`) to compile their
+    /// hypernyms from. Without either there are none.
+    pub fn has_topics(&self) -> bool {
+        self.stemmer.has_synsets(NOUN) || !self.stemmer.compiled_synsets[NOUN].is_empty()
+    }
+
+    /// The lexicographer categories of the words, empty if the dictionary
+    /// has no `dbfiles/`.
+    pub fn categories(&self) -> &Categories {
+        &self.categories
+    }
+
+    /// The document frequencies words are weighted with, empty unless
+    /// configured with [`SummaryBuilder::idf`](struct.SummaryBuilder.html#method.idf).
+    pub fn idf(&self) -> &Idf {
+        &self.idf
+    }
+
+    /// Counts the document frequencies of the lemmas of every file below
+    /// `corpus` (one document per file, directories are walked
+    /// recursively), for use with [`SummaryBuilder::idf`](struct.SummaryBuilder.html#method.idf).
+    ///
+    /// Hidden files and directories, like `.DS_Store`, and files that are
+    /// not UTF-8 text are skipped, with a warning for each.
+    pub fn build_idf<P: AsRef<Path>>(&self, corpus: P) -> io::Result<(Idf, Vec<String>)> {
+        let mut idf = Idf::new();
+        let mut warnings = Vec::new();
+        let mut directories = vec![corpus.as_ref().to_path_buf()];
+
+        while let Some(directory) = directories.pop() {
+            let mut entries = fs::read_dir(&directory)
+                .and_then(|entries| entries.map(|entry| entry.map(|entry| entry.path())).collect::<io::Result<Vec<_>>>())
+                .map_err(|e| path_error(&directory, e))?;
+
+            // documents are counted in a fixed order
+            entries.sort();
+
+            for path in entries {
+                if path.file_name().map_or(false, |name| name.to_string_lossy().starts_with('.')) {
+                    warnings.push(format!("{} is hidden, skipped it", path.display()));
+                    continue;
+                }
+
+                if path.is_dir() {
+                    directories.push(path);
+                    continue;
+                }
+
+                let mut bytes = Vec::new();
+                File::open(&path)
+                    .and_then(|mut file| file.read_to_end(&mut bytes))
+                    .map_err(|e| path_error(&path, e))?;
+
+                match String::from_utf8(bytes) {
+                    Ok(text) => idf.add_document(self.tokenize(&text, 0).into_iter().map(|token| token.lemma)),
+                    Err(_) => warnings.push(format!("{} is not UTF-8 text, skipped it", path.display())),
+                }
+            }
         }
+
+        Ok((idf, warnings))
     }
 
     fn cut(&self, phrase: &str) -> String {
@@ -40,55 +363,354 @@ impl Summary {
         self.stemmer.lemma_phrase(ADV, &a)
     }
 
-    fn process_words(&self, phrase: &str) -> String {
-        let ref stop_words = self.stop_words;
+    /*
+        ~~Algorithm draft~~
+
+        get word frequency
+        determine word type
+        apply penalty:
+            noun: x6
+            noun + verb: x3
+            verb: x2
+            verb + {adj, adv}: x1
+            adj, adv: x1
+    */
+    fn multiplier(&self, word: &str) -> u32 {
+        let (is_noun, is_verb, is_adj, is_adv) = self.stemmer.word_type(word);
 
-        phrase
-            .to_lowercase()
-            .split_whitespace()
-            .filter_map(|word| if !stop_words.contains(word) {
-                            Some(word)
-                        } else {
-                            None
-                        })
-            .collect::<Vec<&str>>()
-            .join(" ")
+        if is_noun {
+            if is_verb || is_adj || is_adv {
+                3u32
+            } else {
+                6u32
+            }
+        } else if is_verb {
+            if !(is_adj || is_adv) { 2u32 } else { 1u32 }
+        } else {
+            1u32
+        }
     }
 
-    fn process_phrases(&self, phrases: &str) -> Vec<Vec<String>> {
-        katana::cut(&phrases.to_string())
+    /* strip punctuation and case */
+    fn process_word(&self, word: &str) -> String {
+        word.to_lowercase()
+            .replace(".", "")
+            .replace(",", "")
+            .replace("\"", "")
+            .replace("”", "")
+            .replace("’", "")
+            .replace("‘", "")
+            .replace("“", "")
+            .replace("'", "")
+    }
+
+    /* stop words and punctuation-only words are not tokens */
+    fn is_stop_word(&self, processed: &str) -> bool {
+        processed.is_empty() || self.stop_words.contains(processed)
+    }
+
+    /*
+        The longest WordNet collocation, like "blood pressure", starting
+        with the first of `words` and its number of words.
+
+        Collocations neither start nor end with a stop word and do not
+        span punctuation.
+    */
+    fn collocation(&self, words: &[(usize, &str)], processed: &[String]) -> Option<(String, usize)> {
+        let longest = ::std::cmp::min(self.stemmer.collocation_words, words.len());
+
+        for n in (2..longest + 1).rev() {
+            if self.is_stop_word(&processed[0]) || self.is_stop_word(&processed[n - 1]) {
+                continue;
+            }
+
+            let separated = words[..n - 1].iter().any(|&(_, word)| !word.ends_with(char::is_alphanumeric)) ||
+                            words[1..n].iter().any(|&(_, word)| !word.starts_with(char::is_alphanumeric));
+
+            if separated {
+                continue;
+            }
+
+            let parts = processed[..n].iter().map(|word| word.as_str()).collect::<Vec<&str>>();
+
+            if let Some(lemma) = self.stemmer.collocation(&parts) {
+                return Some((lemma, n));
+            }
+        }
+
+        None
+    }
+
+    /*
+        Words of `phrase` that are not stop words, lemmatized, with known
+        collocations matched greedily as single tokens.
+    */
+    fn tokenize(&self, phrase: &str, offset: usize) -> Vec<Token> {
+        let words = words(phrase);
+        let processed = words.iter().map(|&(_, word)| self.process_word(word)).collect::<Vec<String>>();
+
+        // the word itself, without surrounding quotes or punctuation
+        let locate = |(start, word): (usize, &str)| {
+            let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric());
+
+            if trimmed.is_empty() {
+                Span { start: offset + start, end: offset + start + word.len() }
+            } else {
+                let start = offset + start + word.find(trimmed).unwrap();
+                Span { start, end: start + trimmed.len() }
+            }
+        };
+
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < words.len() {
+            if let Some((lemma, n)) = self.collocation(&words[i..], &processed[i..]) {
+                tokens.push(Token {
+                    lemma,
+                    span: Span { start: locate(words[i]).start, end: locate(words[i + n - 1]).end },
+                });
+
+                i = i + n;
+                continue;
+            }
+
+            if !self.is_stop_word(&processed[i]) {
+                tokens.push(Token { lemma: self.cut(&processed[i]), span: locate(words[i]) });
+            }
+
+            i = i + 1;
+        }
+
+        tokens
+    }
+
+    fn process_phrases(&self, phrases: &str, document: usize) -> Vec<Sentence> {
+        katana::cut_with_spans(phrases)
+            .into_iter()
+            .enumerate()
+            .map(|(position, (text, span))| {
+                let tokens = if span.is_empty() {
+                    // the sentence could not be located, so neither can its words
+                    self.tokenize(&text, 0)
+                        .into_iter()
+                        .map(|token| Token { lemma: token.lemma, span })
+                        .collect()
+                } else {
+                    self.tokenize(&phrases[span.start..span.end], span.start)
+                };
+
+                Sentence { text, span, tokens, document, position }
+            })
+            .collect()
+    }
+
+    /*
+        Algorithm:
+            for each word in phrase
+                build weight from
+                    absolute keyword frequency
+                    word type multiplier
+                    inverse document frequency (1 without corpus statistics)
+            phrase weight is the sum of its word weights
+    */
+    fn frequency_weights(&self, cut_phrases: &Vec<Vec<&str>>, keyword_frequency: &HashMap<String, u32>) -> Vec<f64> {
+        cut_phrases
             .iter()
             .map(|phrase| {
-                vec!(self.cut(
-                    &self.process_words(
-                        &phrase.replace(".", "")
-                               .replace(",", "")
-                               .replace("\"", "")
-                               .replace("”", "")
-                               .replace("’", "")
-                               .replace("‘", "")
-                               .replace("“", "")
-                               .replace("'", "")
-                    )
-                ), phrase.to_string())
+                let mut weight = 0f64;
+
+                for word in phrase.iter() {
+                    let word_weight = *keyword_frequency.get(*word).unwrap();
+
+                    let multiplier = self.multiplier(word);
+
+                    weight = weight + (word_weight * multiplier) as f64 * self.idf.weight(word);
+                }
+
+                weight
             })
             .collect()
     }
 
-    pub fn summarize(&mut self, phrases: &str, max_phrases: u32) -> (Vec<String>, Vec<String>) {
-        let phrases = self.process_phrases(phrases);
+    /*
+        Keyphrase candidates are runs of words that
 
-        let mut keyword_frequency: HashMap<String, u32> = HashMap::new();
+            are not separated by stop words or punctuation
+            can be nouns or adjectives
+            end with a word that can be a noun
+
+        of at most MAX_KEYPHRASE_WORDS words.
+    */
+    fn keyphrase_candidates<'a>(&self, documents: &[&str], sentences: &'a [Sentence]) -> Vec<Candidate<'a>> {
+        let mut candidates = Vec::new();
+
+        for sentence in sentences.iter() {
+            // without their positions, adjacent words cannot be told apart
+            if sentence.span.is_empty() {
+                continue;
+            }
 
-        let cut_phrases = phrases
+            let text = documents[sentence.document];
+            let mut runs: Vec<Vec<(&Token, (bool, bool, bool, bool))>> = vec![Vec::new()];
+
+            for token in sentence.tokens.iter() {
+                let (noun, verb, adj, adv) = self.stemmer.word_type(&token.lemma);
+                // "very", "down", ... are adjectives as well, but mostly modify what follows
+                let member = (noun || adj) && !adv;
+
+                let adjacent = runs
+                    .last()
+                    .and_then(|run| run.last())
+                    .map(|&(last, _)| text[last.span.end..token.span.start].trim().is_empty())
+                    .unwrap_or(false);
+
+                if !adjacent || !member {
+                    runs.push(Vec::new());
+                }
+
+                if member {
+                    runs.last_mut().unwrap().push((token, (noun, verb, adj, adv)));
+                }
+            }
+
+            for mut run in runs {
+                // phrases end in a noun and do not start with what is likely the verb of the sentence
+                while run.last().map(|&(_, (noun, _, _, _))| !noun).unwrap_or(false) {
+                    run.pop();
+                }
+
+                while run.len() > 1 && run.first().map(|&(_, (_, verb, adj, _))| verb && !adj).unwrap_or(false) {
+                    run.remove(0);
+                }
+
+                if run.is_empty() || run.len() > MAX_KEYPHRASE_WORDS {
+                    continue;
+                }
+
+                candidates.push(Candidate {
+                    lemmas: run.iter().map(|&(token, _)| token.lemma.as_str()).collect(),
+                    document: sentence.document,
+                    span: Span { start: run[0].0.span.start, end: run[run.len() - 1].0.span.end },
+                });
+            }
+        }
+
+        candidates
+    }
+
+    /// Returns the `max_phrases` highest weighted phrases of `phrases` (in
+    /// document order) and its noun keywords, most salient first.
+    pub fn summarize(&self, phrases: &str, max_phrases: u32) -> (Vec<String>, Vec<String>) {
+        let options = SummaryOptions { length: Length::Phrases(max_phrases), ..SummaryOptions::default() };
+        let digest = self.digest(phrases, &options);
+
+        (digest.phrases.into_iter().map(|phrase| phrase.text).collect(),
+         digest.keywords.into_iter().map(|keyword| keyword.word).collect())
+    }
+
+    /// Like [`summarize`](#method.summarize), but keeps the weight and the
+    /// position of every phrase and the frequency and word types of every
+    /// keyword.
+    ///
+    /// Phrases are ordered as requested by `options.order`, keywords by
+    /// salience, most salient first.
+    ///
+    /// # Ranking
+    ///
+    /// The result only depends on the text, the options and the
+    /// dictionary:
+    ///
+    /// * phrases are ranked by score, highest first, and phrases of equal
+    ///   score by position, earliest first; phrases are picked in that
+    ///   order (or, with `lambda`, the first of equally good candidates in
+    ///   that order), so ties at the cut off go to the earlier phrase;
+    /// * keywords are ranked by salience, then by first occurrence, then
+    ///   alphabetically.
+    ///
+    /// Changes to this order are caught by the golden files in
+    /// `tests/golden/`.
+    pub fn digest(&self, phrases: &str, options: &SummaryOptions) -> Digest {
+        self.digest_all(&[phrases], options, false)
+    }
+
+    /// Summarizes several related texts at once, e.g. an article and the
+    /// pages it links to.
+    ///
+    /// Works like [`digest`](#method.digest) on all phrases of all texts,
+    /// with keyword frequencies counted across the texts, except that of
+    /// near identical phrases only the first is eligible for the summary.
+    /// The `document` of every phrase and keyword occurrence is its index
+    /// in `documents`, and positions are relative to that document.
+    pub fn digest_documents(&self, documents: &[&str], options: &SummaryOptions) -> Digest {
+        self.digest_all(documents, options, true)
+    }
+
+    /*
+        Replaces the lemmas of synonyms and derivationally related words by
+        the representative of their concept, see synonyms::concepts.
+
+        Returns the words merged into every representative, in order of
+        first occurrence.
+    */
+    fn merge_synonyms(&self, sentences: &mut [Sentence]) -> HashMap<String, Vec<String>> {
+        let mut frequency: HashMap<&str, u32> = HashMap::new();
+        let mut order = Vec::new();
+
+        for token in sentences.iter().flat_map(|sentence| sentence.tokens.iter()) {
+            let count = frequency.entry(token.lemma.as_str()).or_insert(0);
+
+            if *count == 0 {
+                order.push(token.lemma.as_str());
+            }
+
+            *count = *count + 1;
+        }
+
+        let lemmas = order
             .iter()
-            .map(|phrase| phrase[0].split(" ").collect::<Vec<&str>>())
-            .collect::<Vec<Vec<&str>>>();
+            .map(|&lemma| Lemma { lemma, frequency: frequency[lemma] })
+            .collect::<Vec<Lemma>>();
+
+        let representatives = synonyms::concepts(&self.stemmer, &lemmas);
 
-        let in_phrases = phrases
+        let mut variants: HashMap<String, Vec<String>> = HashMap::new();
+
+        for lemma in order.iter() {
+            if let Some(representative) = representatives.get(*lemma) {
+                variants.entry(representative.clone()).or_insert(Vec::new()).push(lemma.to_string());
+            }
+        }
+
+        for token in sentences.iter_mut().flat_map(|sentence| sentence.tokens.iter_mut()) {
+            if let Some(representative) = representatives.get(&token.lemma) {
+                token.lemma = representative.clone();
+            }
+        }
+
+        variants
+    }
+
+    fn digest_all(&self, documents: &[&str], options: &SummaryOptions, deduplicate: bool) -> Digest {
+        let mut sentences = documents
             .iter()
-            .map(|phrase| phrase[1].clone())
-            .collect::<Vec<String>>();
+            .enumerate()
+            .flat_map(|(document, text)| self.process_phrases(text, document))
+            .collect::<Vec<Sentence>>();
+
+        let variants = if options.merge_synonyms {
+            self.merge_synonyms(&mut sentences)
+        } else {
+            HashMap::new()
+        };
+
+        let mut keyword_frequency: HashMap<String, u32> = HashMap::new();
+        let mut keyword_occurrences: HashMap<&str, Vec<(usize, Span)>> = HashMap::new();
+
+        let cut_phrases = sentences
+            .iter()
+            .map(|sentence| sentence.tokens.iter().map(|token| token.lemma.as_str()).collect::<Vec<&str>>())
+            .collect::<Vec<Vec<&str>>>();
 
         /* populate keyword frequency map */
         for phrase in cut_phrases.iter() {
@@ -99,133 +721,367 @@ impl Summary {
             }
         }
 
-        let mut phrase_weights: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+        for sentence in sentences.iter() {
+            for token in sentence.tokens.iter() {
+                keyword_occurrences
+                    .entry(token.lemma.as_str())
+                    .or_insert(Vec::new())
+                    .push((sentence.document, token.span));
+            }
+        }
+
+        let weights = match options.algorithm {
+            Algorithm::Frequency => self.frequency_weights(&cut_phrases, &keyword_frequency),
+            Algorithm::TextRank => textrank::rank(&cut_phrases),
+        };
+
+        let weights = weights
+            .into_iter()
+            .zip(cut_phrases.iter())
+            .map(|(weight, phrase)| options.normalization.apply(weight, phrase.len()))
+            .enumerate()
+            .map(|(i, weight)| {
+                let mut prior = 1.0 + options.lead_bias / (sentences[i].position + 1) as f64;
+
+                if paragraph_start(documents, &sentences, i) {
+                    prior = prior + options.paragraph_bias;
+                }
+
+                weight * prior
+            })
+            .collect::<Vec<f64>>();
+
+        /* favour phrases that contain the lemmas of the query */
+        let query_terms = options.query
+            .as_ref()
+            .map(|query| {
+                // queries are often questions, drop "?" and the like
+                let query = query
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() || c == '-' { c } else { ' ' })
+                    .collect::<String>();
+
+                self.tokenize(&query, 0)
+                    .into_iter()
+                    .map(|token| {
+                        // merged synonyms are found by their representative
+                        variants
+                            .iter()
+                            .find(|&(_, merged)| merged.contains(&token.lemma))
+                            .map(|(representative, _)| representative.clone())
+                            .unwrap_or(token.lemma)
+                    })
+                    .collect::<HashSet<String>>()
+            })
+            .unwrap_or(HashSet::new());
+
+        let weights = if query_terms.is_empty() {
+            weights
+        } else {
+            weights
+                .into_iter()
+                .zip(cut_phrases.iter())
+                .map(|(weight, phrase)| {
+                    let matched = query_terms
+                        .iter()
+                        .filter(|term| phrase.contains(&term.as_str()))
+                        .count();
+
+                    weight * (1.0 + QUERY_BOOST * matched as f64 / query_terms.len() as f64)
+                })
+                .collect()
+        };
+
+        let costs = sentences
+            .iter()
+            .map(|sentence| Cost { words: words(&sentence.text).len(), chars: sentence.text.chars().count() })
+            .collect::<Vec<Cost>>();
+
+        /* phrases in rank order, of near duplicates only the first */
+        let ranking = if deduplicate {
+            let duplicates = selection::duplicates(&cut_phrases);
+
+            selection::rank(&weights).into_iter().filter(|&i| !duplicates[i]).collect()
+        } else {
+            selection::rank(&weights)
+        };
+
+        let mut budget = match options.length {
+            Length::Phrases(phrases) => Budget::Phrases(phrases as usize),
+            Length::Ratio(ratio) => Budget::Phrases((ratio * ranking.len() as f64).ceil() as usize),
+            Length::Words(words) => Budget::Words(words as usize),
+            Length::Chars(chars) => Budget::Chars { left: chars as usize, separator: 0 },
+        };
+
+        /* pick the best phrases by weight (or by marginal relevance) that fit the budget */
+        let mut picked = match options.lambda {
+            Some(lambda) => selection::mmr(ranking, &weights, &cut_phrases, &costs, lambda, &mut budget),
+            None => selection::top(ranking, &costs, &mut budget),
+        };
 
-        let mut i: u32 = 0;
+        match options.order {
+            Order::Document => picked.sort(),
+            Order::Score => picked.sort_by(|&a, &b| {
+                weights[b].partial_cmp(&weights[a]).unwrap_or(Ordering::Equal)
+                    .then(a.cmp(&b))
+            }),
+        }
+
+        let phrases = picked
+            .iter()
+            .map(|&entry| Phrase {
+                text: sentences[entry].text.clone(),
+                document: sentences[entry].document,
+                index: sentences[entry].position,
+                score: weights[entry],
+                span: sentences[entry].span,
+            })
+            .collect();
 
         /*
-            populate keyword frequency map
-
-            Algorithm:
-                for each word in phrase
-                    build weight from
-                        absolute keyword frequency
-                        word type multiplier
-                    insert offset into weight map at index [weight]
-
-            Trivia:
-                For a set [a, b, c] (where a, b, c are words in a phrase)
-                compute weight and obtain index, so that a sorted map
-                {weight1: [a], weight2: [b, c], ..} can be built.
-
-                This allows for more space-efficient tracking of top
-                phrases and for a trivial top-down iteration.
+            Rank keywords (words that are nouns only, like "water" but not
+            "find" or "red") by salience:
+
+                frequency * word type multiplier * position bonus * idf
+
+            where the position bonus falls linearly from
+            1 + KEYWORD_POSITION_BONUS for words first seen in the first
+            sentence to 1 for words first seen in the last one.
+
+            Ties are broken by first occurrence, then alphabetically.
         */
+        let mut first_seen: HashMap<&str, (usize, usize)> = HashMap::new();
+        let mut ordinal = 0;
 
-        for phrase in cut_phrases.iter() {
-            let mut weight = 0u32;
+        for (i, sentence) in sentences.iter().enumerate() {
+            for token in sentence.tokens.iter() {
+                first_seen.entry(token.lemma.as_str()).or_insert((i, ordinal));
+                ordinal = ordinal + 1;
+            }
+        }
 
-            for word in phrase.iter() {
-                let word_weight = *keyword_frequency.get(*word).unwrap();
-
-                /*
-                    ~~Algorithm draft~~
-
-                    get word frequency
-                    determine word type
-                    apply penalty:
-                        noun: x6
-                        noun + verb: x3
-                        verb: x2
-                        verb + {adj, adv}: x1
-                        adj, adv: x1
-                */
-
-                let multiplier = {
-                    let (is_noun, is_verb, is_adj, is_adv) = self.stemmer.word_type(word);
-
-                    if is_noun {
-                        if is_verb || is_adj || is_adv {
-                            3u32
-                        } else {
-                            6u32
-                        }
-                    } else if is_verb {
-                        if !(is_adj || is_adv) { 2u32 } else { 1u32 }
-                    } else {
-                        1u32
-                    }
+        let mut ranked_keywords = keyword_frequency
+            .iter()
+            .filter_map(|(word, &frequency)| {
+                let (noun, verb, adj, adv) = self.stemmer.word_type(word);
+
+                if !noun || verb || adj || adv {
+                    return None;
+                }
+
+                let (first_sentence, first_ordinal) = first_seen[word.as_str()];
+
+                let position_bonus = 1.0 + KEYWORD_POSITION_BONUS *
+                    (1.0 - first_sentence as f64 / sentences.len() as f64);
+
+                let keyword = Keyword {
+                    // collocations are joined with underscores in WordNet
+                    word: word.replace('_', " "),
+                    frequency,
+                    score: (frequency * self.multiplier(word)) as f64 * position_bonus * self.idf.weight(word),
+                    occurrences: keyword_occurrences[word.as_str()].clone(),
+                    variants: variants
+                        .get(word.as_str())
+                        .map(|variants| variants.iter().map(|variant| variant.replace('_', " ")).collect())
+                        .unwrap_or(Vec::new()),
+                    noun,
+                    verb,
+                    adj,
+                    adv,
                 };
 
-                weight = weight + (word_weight * multiplier);
-            }
+                Some((keyword, first_ordinal))
+            })
+            .collect::<Vec<(Keyword, usize)>>();
 
-            /* insert a weight relation: weight -> [phrases, ..] */
-            let mut weight_map = match phrase_weights.get(&weight) {
-                Some(map) => (*map).clone(),
-                None => BTreeSet::new(),
-            };
+        ranked_keywords.sort_by(|&(ref a, a_first), &(ref b, b_first)| {
+            b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+                .then(a_first.cmp(&b_first))
+                .then(a.word.cmp(&b.word))
+        });
 
-            weight_map.insert(i);
+        let topics = if options.num_topics > 0 {
+            let mut topics = self.topics(ranked_keywords.iter().take(TOPIC_KEYWORDS).map(|&(ref keyword, _)| keyword));
+            topics.truncate(options.num_topics as usize);
+            topics
+        } else {
+            Vec::new()
+        };
 
-            phrase_weights.insert(weight, weight_map);
+        let categories = if options.categories {
+            self.categories.profile(cut_phrases.iter().flat_map(|phrase| phrase.iter()))
+        } else {
+            Vec::new()
+        };
 
-            i = i + 1;
+        if let Some(num_keywords) = options.num_keywords {
+            ranked_keywords.truncate(num_keywords as usize);
         }
 
-        /*
-            From BTreeMap<weight: u32, phrases: Vec<u32>> map top max_phrases
-            into a BTreeSet (sorted).
-        */
+        let keywords = ranked_keywords
+            .into_iter()
+            .map(|(keyword, _)| keyword)
+            .collect();
 
-        let mut out_set: BTreeSet<u32> = BTreeSet::new();
+        let mut keyphrases = keyphrases::rake(&self.keyphrase_candidates(documents, &sentences));
 
-        let mut k = 0u32;
+        if let Some(num_keyphrases) = options.num_keyphrases {
+            keyphrases.truncate(num_keyphrases as usize);
+        }
 
-        for (_, weight_set) in phrase_weights.iter().rev() {
-            for entry in weight_set.iter() {
-                k = k + 1;
+        Digest { phrases, keywords, keyphrases, topics, categories }
+    }
 
-                if max_phrases < k {
-                    break;
-                }
+    /*
+        Topics of the most frequent noun sense of `keywords`, see
+        topics::topics. Without a noun data file the senses and hypernyms
+        compiled from the lexicographer files stand in, see has_topics.
+    */
+    fn topics<'a, I>(&self, keywords: I) -> Vec<Topic>
+        where I: Iterator<Item = &'a Keyword>
+    {
+        let lemmas = keywords
+            .map(|keyword| (keyword.word.replace(' ', "_"), keyword.score))
+            .collect::<Vec<(String, f64)>>();
 
-                out_set.insert(*entry);
-            }
+        let compiled = !self.stemmer.has_synsets(NOUN);
+        let offsets = if compiled { &self.stemmer.compiled_senses } else { &self.stemmer.lemma_pos_offset_map };
 
-            if max_phrases < k {
-                break;
-            }
+        let senses = lemmas
+            .iter()
+            .filter_map(|&(ref lemma, score)| {
+                offsets
+                    .get(lemma)
+                    .and_then(|parts| parts.get(&NOUN))
+                    .and_then(|offsets| offsets.first())
+                    .map(|&synset| Sense { word: lemma, score, synset: synset as usize })
+            })
+            .collect::<Vec<Sense>>();
+
+        let mut topics = topics::topics(&senses, |offset| {
+            let synset = if compiled {
+                self.stemmer.compiled_synsets[NOUN].get(offset).cloned()
+            } else {
+                self.stemmer.synset(NOUN, offset).ok()
+            };
+
+            synset.map(|synset| {
+                let mut hypernyms = synset.related(Relation::Hypernym);
+                hypernyms.extend(synset.related(Relation::InstanceHypernym));
+
+                Concept {
+                    label: synset.lemmas[0].replace('_', " "),
+                    hypernyms: hypernyms.into_iter().map(|pointer| pointer.offset).collect(),
+                }
+            })
+        });
+
+        for topic in topics.iter_mut() {
+            topic.keywords = topic.keywords.iter().map(|keyword| keyword.replace('_', " ")).collect();
         }
 
-        /*
-            build output tuple: (Vec<String>, Vec<String>) -- (phrases, keywords)
-        */
-        (out_set
-             .iter()
-             .map(|entry| in_phrases[*entry as usize].clone())
-             .collect(),
-         {
-             let mut kf_tuples = keyword_frequency
-                 .iter()
-                 .map(|(k, v)| (k.to_owned(), *v))
-                 .collect::<Vec<(String, u32)>>();
-
-             kf_tuples.sort_by(|a, b| a.1.cmp(&b.1));
-
-             kf_tuples
-                    .iter()
-                    // filter out non-nouns
-                    // map to strings
-                    .filter_map(move |&(ref a, _)|
-                        match self.stemmer.word_type(a) {
-                        //   noun  verb   adj.   adv.
-                            (true, false, false, false) =>
-                                Some(a.to_string()),
-                            _ => None
-                        }
-                    )
-                    .collect()
-         })
+        topics
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{paragraph_start, Sentence, Summary, SummaryBuilder};
+    use super::katana::Span;
+
+    use std::sync::{Arc, Barrier};
+    use std::thread;
+
+    const TEXT: &'static str = "The planet holds the potential for harboring liquid water under certain circumstances. \
+        Scientists have estimated the depth of the possible liquid reservoirs as anywhere between tens of meters to kilometers. \
+        Recent observations from orbiters have revealed mysterious dark streaks that seasonally flow down the sides of craters. \
+        These streaks could conceivably be brines of liquid water fed by aquifers very close to the surface.";
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn summary_is_shareable() {
+        assert_send_sync::<Summary>();
+    }
+
+    #[test]
+    fn paragraphs_start_after_line_breaks() {
+        let text = "One. Two.\n\nThree. Four.";
+        let sentences = [(0, 4), (5, 9), (11, 17), (18, 23)]
+            .iter()
+            .enumerate()
+            .map(|(position, &(start, end))| Sentence {
+                text: text[start..end].to_string(),
+                span: Span { start, end },
+                tokens: Vec::new(),
+                document: 0,
+                position,
+            })
+            .collect::<Vec<Sentence>>();
+
+        let starts = (0..sentences.len())
+            .map(|i| paragraph_start(&[text], &sentences, i))
+            .collect::<Vec<bool>>();
+
+        assert_eq!(starts, vec![true, false, true, false]);
+    }
+
+    #[test]
+    fn compiled_nouns_are_merged_without_derivations() {
+        // bank and shore in dbfiles/noun.object, arrive with a derivation pointer in data.verb that
+        // is the offset of some noun in data.noun, not the first compiled synset
+        let summary = Summary::with_dictionary("tests/fixtures/compiled-synonyms-dict").unwrap();
+        let mut sentences = summary.process_phrases("We arrive at the bank. The bank is a shore.", 0);
+
+        let variants = summary.merge_synonyms(&mut sentences);
+
+        assert_eq!(variants.into_iter().collect::<Vec<_>>(), vec![("bank".to_string(), vec!["shore".to_string()])]);
+    }
+
+    #[test]
+    fn builder_defaults_ignore_the_environment() {
+        let vars = |name: &str| match name {
+            "IDF_PATH" => Some("/nonexistent/idf".to_string()),
+            _ => None,
+        };
+
+        let builder = SummaryBuilder::new();
+        let from_vars = SummaryBuilder::from_vars(vars);
+
+        assert_eq!((builder.dict_path, builder.idf_path), ("./dict/".to_string(), None));
+        assert_eq!((from_vars.dict_path, from_vars.idf_path), ("./dict/".to_string(), Some("/nonexistent/idf".to_string())));
+    }
+
+    /*
+        Two threads borrow the one Summary and are released into summarize
+        together by the barrier, twice over; both get the result of a call
+        made alone.
+    */
+    #[test]
+    fn concurrent_summaries_share_one_summary() {
+        const THREADS: usize = 2;
+
+        let summary = Arc::new(Summary::with_dictionary("tests/fixtures/dict").unwrap());
+        let barrier = Arc::new(Barrier::new(THREADS));
+        let expected = summary.summarize(TEXT, 2);
+
+        let workers = (0..THREADS)
+            .map(|_| {
+                let (summary, barrier) = (summary.clone(), barrier.clone());
+
+                thread::spawn(move || {
+                    let summary: &Summary = &summary;
+
+                    (0..2).map(|_| {
+                        barrier.wait();
+                        summary.summarize(TEXT, 2)
+                    }).collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), vec![expected.clone(), expected.clone()]);
+        }
     }
 }
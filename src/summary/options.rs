@@ -0,0 +1,220 @@
+use std::str::FromStr;
+
+use error::SummaryError;
+
+/// How phrases are weighted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Sum of the frequencies of a phrase's words in the text, each
+    /// multiplied by a factor for its word type (nouns weigh most) and, if
+    /// configured, by its [inverse document frequency](struct.Idf.html).
+    Frequency,
+    /// TextRank: phrases are ranked like web pages by PageRank, on a graph
+    /// whose edges are the lemmas two phrases share.
+    TextRank,
+}
+
+impl Default for Algorithm {
+    fn default() -> Algorithm {
+        Algorithm::Frequency
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = SummaryError;
+
+    /// Parses `"frequency"` or `"textrank"`.
+    fn from_str(value: &str) -> Result<Algorithm, SummaryError> {
+        match value {
+            "frequency" => Ok(Algorithm::Frequency),
+            "textrank" => Ok(Algorithm::TextRank),
+            _ => Err(SummaryError::InvalidOption { name: "algorithm", value: value.to_string() }),
+        }
+    }
+}
+
+/// Default `Normalization::TargetLength`, in content words.
+pub const DEFAULT_TARGET_LENGTH: usize = 12;
+
+/// How phrase weights are adjusted for phrase length, so long phrases do
+/// not win merely by containing more words.
+///
+/// Lengths are counted in content words, i.e. words that are not stop
+/// words.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Normalization {
+    /// Weights are used as they are.
+    None,
+    /// Weight per content word.
+    Average,
+    /// Weight divided by `1 + ln(length)`, damping rather than removing
+    /// the advantage of long phrases.
+    Log,
+    /// Weight divided by `1 + |length - target| / target`, favouring
+    /// phrases close to the target length.
+    TargetLength(usize),
+}
+
+impl Normalization {
+    /// Adjusts the `weight` of a phrase of `length` content words.
+    pub fn apply(&self, weight: f64, length: usize) -> f64 {
+        let length = length.max(1) as f64;
+
+        match *self {
+            Normalization::None => weight,
+            Normalization::Average => weight / length,
+            Normalization::Log => weight / (1.0 + length.ln()),
+            Normalization::TargetLength(target) => {
+                let target = target.max(1) as f64;
+
+                weight / (1.0 + (length - target).abs() / target)
+            }
+        }
+    }
+}
+
+impl Default for Normalization {
+    fn default() -> Normalization {
+        Normalization::None
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = SummaryError;
+
+    /// Parses `"none"`, `"average"`, `"log"` or `"target"` (with
+    /// `DEFAULT_TARGET_LENGTH`).
+    fn from_str(value: &str) -> Result<Normalization, SummaryError> {
+        match value {
+            "none" => Ok(Normalization::None),
+            "average" => Ok(Normalization::Average),
+            "log" => Ok(Normalization::Log),
+            "target" => Ok(Normalization::TargetLength(DEFAULT_TARGET_LENGTH)),
+            _ => Err(SummaryError::InvalidOption { name: "normalization", value: value.to_string() }),
+        }
+    }
+}
+
+/// How long a summary is. Phrases are never cut: the best phrases that fit
+/// are picked, skipping those that would overrun the budget.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    /// A number of phrases.
+    Phrases(u32),
+    /// A fraction (between 0 and 1) of the phrases of the text, rounded up.
+    Ratio(f64),
+    /// At most this many words, counted as whitespace separated words.
+    Words(u32),
+    /// At most this many characters, counting a space between phrases.
+    Chars(u32),
+}
+
+impl Default for Length {
+    fn default() -> Length {
+        Length::Phrases(3)
+    }
+}
+
+/// Order of the selected phrases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Order {
+    /// As they appear in the text.
+    Document,
+    /// Highest weighted first, phrases of equal weight in document order.
+    Score,
+}
+
+impl Default for Order {
+    fn default() -> Order {
+        Order::Document
+    }
+}
+
+impl FromStr for Order {
+    type Err = SummaryError;
+
+    /// Parses `"document"` or `"score"`.
+    fn from_str(value: &str) -> Result<Order, SummaryError> {
+        match value {
+            "document" => Ok(Order::Document),
+            "score" => Ok(Order::Score),
+            _ => Err(SummaryError::InvalidOption { name: "order", value: value.to_string() }),
+        }
+    }
+}
+
+/// Tunes what [`Summary::digest`](struct.Summary.html#method.digest)
+/// selects.
+///
+/// ```
+/// use summary::SummaryOptions;
+///
+/// let options = SummaryOptions { num_keywords: Some(10), ..SummaryOptions::default() };
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummaryOptions {
+    /// How many phrases to select.
+    pub length: Length,
+    /// Order of the selected phrases.
+    pub order: Order,
+    /// Maximum number of keywords to return, all of them if `None`.
+    pub num_keywords: Option<u32>,
+    /// Maximum number of keyphrases to return, all of them if `None`.
+    pub num_keyphrases: Option<u32>,
+    /// Number of [topics](struct.Topic.html) to return, 0 skips looking
+    /// them up. There are none if the dictionary has neither noun synsets
+    /// nor noun lexicographer files, see
+    /// [`Summary::has_topics`](struct.Summary.html#method.has_topics).
+    pub num_topics: u32,
+    /// How phrases are weighted.
+    pub algorithm: Algorithm,
+    /// How phrase weights are adjusted for phrase length.
+    pub normalization: Normalization,
+    /// Trade-off between weight and novelty when picking phrases, between
+    /// 0 and 1: phrases are picked by `lambda * weight - (1 - lambda) *
+    /// overlap with the phrases already picked`. `None` picks by weight
+    /// alone, as does `Some(1.0)`.
+    pub lambda: Option<f64>,
+    /// Boost for the first phrases of the text: the weight of the `i`th
+    /// phrase (from 0) is multiplied by `1 + lead_bias / (i + 1)`.
+    pub lead_bias: f64,
+    /// Boost for the first phrase of every paragraph: its weight is
+    /// multiplied by `1 + paragraph_bias`. Paragraphs are separated by
+    /// line breaks.
+    pub paragraph_bias: f64,
+    /// Favours phrases containing the words of the query (compared by
+    /// lemma), so the summary answers it; the number of phrases is
+    /// unaffected.
+    pub query: Option<String>,
+    /// Counts words sharing a meaning in WordNet, like "anxiety" and
+    /// "anxiousness", or derived from one another, like "anxious", as one
+    /// word: the most frequent of them that is only a noun, as keywords
+    /// are, or else that can be one. The others are
+    /// listed as its [`variants`](struct.Keyword.html#structfield.variants).
+    /// Words related to it only through one of its variants are not merged.
+    pub merge_synonyms: bool,
+    /// Profiles the words of the text by their [lexicographer
+    /// categories](struct.Category.html), which needs the lexicographer
+    /// files of the dictionary.
+    pub categories: bool,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> SummaryOptions {
+        SummaryOptions {
+            length: Length::default(),
+            order: Order::default(),
+            num_keywords: None,
+            num_keyphrases: None,
+            num_topics: 0,
+            algorithm: Algorithm::default(),
+            normalization: Normalization::default(),
+            lambda: None,
+            lead_bias: 0.0,
+            paragraph_bias: 0.0,
+            query: None,
+            merge_synonyms: false,
+            categories: false,
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/* phrases sharing this much of their lemmas (Jaccard index) with an earlier phrase are duplicates */
+const DUPLICATE_SIMILARITY: f64 = 0.8;
+
+/* size of a phrase in the units of a Budget */
+#[derive(Clone, Copy)]
+pub struct Cost {
+    pub words: usize,
+    pub chars: usize,
+}
+
+/* what is left of the summary length while phrases are picked */
+pub enum Budget {
+    Phrases(usize),
+    Words(usize),
+    Chars { left: usize, separator: usize },
+}
+
+impl Budget {
+    pub fn fits(&self, cost: &Cost) -> bool {
+        match *self {
+            Budget::Phrases(left) => left > 0,
+            Budget::Words(left) => cost.words <= left,
+            Budget::Chars { left, separator } => cost.chars + separator <= left,
+        }
+    }
+
+    pub fn take(&mut self, cost: &Cost) {
+        match *self {
+            Budget::Phrases(ref mut left) => *left = *left - 1,
+            Budget::Words(ref mut left) => *left = *left - cost.words,
+            Budget::Chars { ref mut left, ref mut separator } => {
+                *left = *left - cost.chars - *separator;
+                // phrases after the first are joined with a space
+                *separator = 1;
+            }
+        }
+    }
+}
+
+/* phrase indices by weight, phrases of equal weight in document order */
+pub fn rank(weights: &[f64]) -> Vec<usize> {
+    let mut ranking = (0..weights.len()).collect::<Vec<usize>>();
+
+    ranking.sort_by(|&a, &b| {
+        weights[b].partial_cmp(&weights[a]).unwrap_or(Ordering::Equal)
+            .then(a.cmp(&b))
+    });
+
+    ranking
+}
+
+/* the best ranked phrases that fit the budget, in the order they were picked */
+pub fn top(ranking: Vec<usize>, costs: &[Cost], budget: &mut Budget) -> Vec<usize> {
+    let mut picked = Vec::new();
+
+    for i in ranking {
+        if budget.fits(&costs[i]) {
+            budget.take(&costs[i]);
+            picked.push(i);
+        }
+    }
+
+    picked
+}
+
+/*
+    Maximal Marginal Relevance (Carbonell & Goldstein, 1998).
+
+    Phrases are picked one at a time, each time the one maximizing
+
+        lambda * weight / max weight - (1 - lambda) * max similarity to the picked phrases
+
+    where similarity is the Jaccard index of the lemma sets, among the
+    phrases of the ranking that still fit the budget. lambda = 1 is plain ranking by
+    weight, lower values trade weight for novelty.
+
+    Returns the picked indices in the order they were picked.
+*/
+pub fn mmr(ranking: Vec<usize>,
+           weights: &[f64],
+           cut_phrases: &Vec<Vec<&str>>,
+           costs: &[Cost],
+           lambda: f64,
+           budget: &mut Budget)
+           -> Vec<usize> {
+    let lemma_sets = cut_phrases
+        .iter()
+        .map(|phrase| phrase.iter().cloned().collect::<HashSet<&str>>())
+        .collect::<Vec<HashSet<&str>>>();
+
+    let max_weight = weights.iter().cloned().fold(0.0, f64::max);
+    let relevance = |i: usize| if max_weight > 0.0 { weights[i] / max_weight } else { 0.0 };
+
+    let mut candidates = ranking;
+    let mut redundancy = vec![0.0f64; weights.len()];
+    let mut picked = Vec::new();
+
+    loop {
+        // the budget only shrinks, so a phrase that does not fit never will
+        candidates.retain(|&candidate| budget.fits(&costs[candidate]));
+
+        if candidates.is_empty() {
+            break;
+        }
+
+        // candidates are in rank order, so ties go to the better ranked phrase
+        let mut best = 0;
+        let mut best_score = ::std::f64::NEG_INFINITY;
+
+        for (position, &candidate) in candidates.iter().enumerate() {
+            let score = lambda * relevance(candidate) - (1.0 - lambda) * redundancy[candidate];
+
+            if score > best_score {
+                best = position;
+                best_score = score;
+            }
+        }
+
+        let chosen = candidates.remove(best);
+
+        budget.take(&costs[chosen]);
+
+        for &candidate in candidates.iter() {
+            let similarity = jaccard(&lemma_sets[chosen], &lemma_sets[candidate]);
+
+            if similarity > redundancy[candidate] {
+                redundancy[candidate] = similarity;
+            }
+        }
+
+        picked.push(chosen);
+    }
+
+    picked
+}
+
+/*
+    For every phrase, whether an earlier phrase is nearly the same, i.e.
+    has a Jaccard index of at least DUPLICATE_SIMILARITY with it.
+
+    Only phrases sharing lemmas are compared, found by an index of the
+    lemmas of the phrases that are not duplicates themselves.
+*/
+pub fn duplicates(cut_phrases: &Vec<Vec<&str>>) -> Vec<bool> {
+    let mut phrases_by_lemma: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut lemma_counts = Vec::new();
+    let mut duplicates = Vec::new();
+
+    for (i, phrase) in cut_phrases.iter().enumerate() {
+        let lemmas = phrase.iter().cloned().collect::<HashSet<&str>>();
+        let mut shared: HashMap<usize, usize> = HashMap::new();
+
+        for lemma in lemmas.iter() {
+            for &other in phrases_by_lemma.get(lemma).map(|others| others.as_slice()).unwrap_or(&[]) {
+                *shared.entry(other).or_insert(0) += 1;
+            }
+        }
+
+        let duplicate = shared.iter().any(|(&other, &count)| {
+            let union = lemmas.len() + lemma_counts[other] - count;
+
+            count as f64 / union as f64 >= DUPLICATE_SIMILARITY
+        });
+
+        if !duplicate {
+            for lemma in lemmas.iter() {
+                phrases_by_lemma.entry(lemma).or_insert(Vec::new()).push(i);
+            }
+        }
+
+        lemma_counts.push(lemmas.len());
+        duplicates.push(duplicate);
+    }
+
+    duplicates
+}
+
+fn jaccard(a: &HashSet<&str>, b: &HashSet<&str>) -> f64 {
+    let union = a.union(b).count();
+
+    if union == 0 {
+        0.0
+    } else {
+        a.intersection(b).count() as f64 / union as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{duplicates, mmr, rank, top, Budget, Cost};
+
+    #[test]
+    fn mmr_skips_near_duplicates() {
+        let weights = vec![10.0, 9.5, 6.0];
+        let costs = vec![Cost { words: 3, chars: 20 }; 3];
+        let phrases = vec![
+            vec!["mars", "water", "life"],
+            vec!["mars", "water", "life", "surface"],
+            vec!["telescope", "orbit"],
+        ];
+
+        assert_eq!(top(rank(&weights), &costs, &mut Budget::Phrases(2)), vec![0, 1]);
+        assert_eq!(mmr(rank(&weights), &weights, &phrases, &costs, 1.0, &mut Budget::Phrases(2)), vec![0, 1]);
+        assert_eq!(mmr(rank(&weights), &weights, &phrases, &costs, 0.5, &mut Budget::Phrases(2)), vec![0, 2]);
+    }
+
+    #[test]
+    fn budgets_are_packed_without_overrunning() {
+        let weights = vec![10.0, 8.0, 6.0, 4.0];
+        let costs = vec![
+            Cost { words: 6, chars: 30 },
+            Cost { words: 8, chars: 40 },
+            Cost { words: 3, chars: 15 },
+            Cost { words: 2, chars: 10 },
+        ];
+
+        // the second phrase does not fit, the smaller ones after it do
+        assert_eq!(top(rank(&weights), &costs, &mut Budget::Words(10)), vec![0, 2]);
+        assert_eq!(top(rank(&weights), &costs, &mut Budget::Chars { left: 57, separator: 0 }), vec![0, 2, 3]);
+        assert_eq!(top(rank(&weights), &costs, &mut Budget::Chars { left: 56, separator: 0 }), vec![0, 2]);
+        assert!(top(rank(&weights), &costs, &mut Budget::Words(1)).is_empty());
+    }
+
+    #[test]
+    fn later_near_duplicates_are_found() {
+        let phrases = vec![
+            vec!["caffeine", "stimulant", "xanthine", "class", "adenosine"],
+            vec!["caffeine", "stimulant", "xanthine", "class", "adenosine", "antagonist"],
+            vec!["caffeine", "stimulant"],
+            vec![],
+            vec!["caffeine", "xanthine", "stimulant", "adenosine", "class"],
+        ];
+
+        assert_eq!(duplicates(&phrases), vec![false, true, false, false, true]);
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+use super::synset::Relation;
+use super::wordnet_stemmer::{WordnetStemmer, NOUN, VERB, ADJ, ADV};
+
+/*
+    only the most frequent senses of a word are considered, rare ones
+    relate too much: "dose" and "acid" share a synset (slang for LSD)
+*/
+const SYNONYM_SENSES: usize = 3;
+
+/* a word of the text to be merged with its synonyms */
+pub struct Lemma<'a> {
+    pub lemma: &'a str,
+    pub frequency: u32,
+}
+
+/*
+    Groups the words of a text (given in order of first occurrence) into
+    concepts: nouns sharing one of their most frequent synsets, like
+    "anxiety" and "anxiousness", and the words derived from them, like
+    "anxious", are related.
+
+    Every concept is a representative and the words related to it
+    directly, not through another word: "worry" shares a synset with
+    "anxiety" and another one with "concern", which is no synonym of
+    "anxiety". Representatives are picked among the words that are left,
+    those that are only nouns (so they can be keywords) first, then those
+    that can be nouns, then the most frequent, then the first one.
+
+    Returns the representative of every word that is not its own.
+
+    Derivations are read from the data files of the dictionary and only
+    found if it has the data files of both parts of speech.
+*/
+pub fn concepts(stemmer: &WordnetStemmer, lemmas: &[Lemma]) -> HashMap<String, String> {
+    let mut related: Vec<HashSet<usize>> = vec![HashSet::new(); lemmas.len()];
+
+    /* the words having a synset among their most frequent senses, see WordnetStemmer::senses */
+    let mut senses: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+
+    for (i, lemma) in lemmas.iter().enumerate() {
+        for &part in [NOUN, VERB, ADJ, ADV].iter() {
+            for &sense in stemmer.senses(part, lemma.lemma).iter().take(SYNONYM_SENSES) {
+                senses.entry((part, sense as usize)).or_insert(Vec::new()).push(i);
+            }
+        }
+    }
+
+    /*
+        Only nouns are merged by shared synsets: verbs have many senses,
+        so "go", "move", "hold" and "continue" would end up as one.
+    */
+    for (&(part, _), words) in senses.iter() {
+        if part == NOUN {
+            for &word in words.iter() {
+                related[word].extend(words.iter().filter(|&&other| other != word));
+            }
+        }
+    }
+
+    /*
+        Derived words are merged with the nouns they are derived from, like
+        "anxious" with "anxiety". Pointers are offsets, the senses of
+        compiled nouns are not.
+    */
+    for &part in [VERB, ADJ, ADV].iter() {
+        if !stemmer.has_data_file(NOUN) || !stemmer.has_data_file(part) {
+            continue;
+        }
+
+        let mut offsets = senses.keys().filter(|&&(other, _)| other == part).map(|&(_, offset)| offset).collect::<Vec<usize>>();
+        offsets.sort();
+
+        let synsets = match stemmer.synsets_at(part, &offsets) {
+            Ok(synsets) => synsets,
+            Err(_) => continue,
+        };
+
+        for synset in synsets.iter() {
+            let words = &senses[&(part, synset.offset)];
+
+            for pointer in synset.related(Relation::DerivationallyRelated).into_iter().filter(|pointer| pointer.part == NOUN) {
+                for &other in senses.get(&(NOUN, pointer.offset)).map(|others| others.as_slice()).unwrap_or(&[]) {
+                    for &word in words.iter().filter(|&&word| word != other) {
+                        related[word].insert(other);
+                        related[other].insert(word);
+                    }
+                }
+            }
+        }
+    }
+
+    // best representatives first, the sort is stable
+    let mut candidates = (0..lemmas.len()).collect::<Vec<usize>>();
+    let kind = |i: usize| match stemmer.word_type(lemmas[i].lemma) {
+        (true, false, false, false) => 0,
+        (true, _, _, _) => 1,
+        _ => 2,
+    };
+    candidates.sort_by(|&a, &b| kind(a).cmp(&kind(b)).then(lemmas[b].frequency.cmp(&lemmas[a].frequency)));
+
+    let mut merged = vec![false; lemmas.len()];
+    let mut representatives = HashMap::new();
+
+    for representative in candidates {
+        if merged[representative] {
+            continue;
+        }
+
+        merged[representative] = true;
+
+        let mut members = related[representative].iter().cloned().filter(|&i| !merged[i]).collect::<Vec<usize>>();
+        members.sort();
+
+        for i in members {
+            merged[i] = true;
+            representatives.insert(lemmas[i].lemma.to_string(), lemmas[representative].lemma.to_string());
+        }
+    }
+
+    representatives
+}
+
+#[cfg(test)]
+mod test {
+    use super::{concepts, Lemma};
+    use summary::wordnet_stemmer::WordnetStemmer;
+
+    #[test]
+    fn words_are_merged_with_related_representatives_only() {
+        // anxiety, anxiousness, concern and worry with their synsets, anxious with a derivation pointer in data.adj
+        let stemmer = WordnetStemmer::new("tests/fixtures/synonyms-dict/").unwrap();
+        let lemmas = vec![Lemma { lemma: "worry", frequency: 2 },
+                          Lemma { lemma: "anxious", frequency: 1 },
+                          Lemma { lemma: "concern", frequency: 1 },
+                          Lemma { lemma: "anxiety", frequency: 3 },
+                          Lemma { lemma: "anxiousness", frequency: 1 }];
+
+        let mut representatives = concepts(&stemmer, &lemmas).into_iter().collect::<Vec<(String, String)>>();
+        representatives.sort();
+
+        // "concern" shares a synset with "worry", not with "anxiety"
+        assert_eq!(representatives,
+                   vec![("anxious".to_string(), "anxiety".to_string()),
+                        ("anxiousness".to_string(), "anxiety".to_string()),
+                        ("worry".to_string(), "anxiety".to_string())]);
+    }
+}
@@ -0,0 +1,224 @@
+use std::io::{self, ErrorKind};
+
+use super::wordnet_stemmer::{NOUN, VERB, ADJ, ADV};
+
+/// Names of the lexicographer files, by number.
+pub const LEXICOGRAPHER_FILES: [&'static str; 45] = [
+    "adj.all", "adj.pert", "adv.all", "noun.Tops", "noun.act", "noun.animal", "noun.artifact",
+    "noun.attribute", "noun.body", "noun.cognition", "noun.communication", "noun.event", "noun.feeling",
+    "noun.food", "noun.group", "noun.location", "noun.motive", "noun.object", "noun.person",
+    "noun.phenomenon", "noun.plant", "noun.possession", "noun.process", "noun.quantity", "noun.relation",
+    "noun.shape", "noun.state", "noun.substance", "noun.time", "verb.body", "verb.change", "verb.cognition",
+    "verb.communication", "verb.competition", "verb.consumption", "verb.contact", "verb.creation",
+    "verb.emotion", "verb.motion", "verb.perception", "verb.possession", "verb.social", "verb.stative",
+    "verb.weather", "adj.ppl",
+];
+
+/// How a synset relates to the synset a pointer points to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Relation {
+    /// The other synset is more general: "breathe" is a kind of "expel".
+    Hypernym,
+    /// The other synset is the class of this instance.
+    InstanceHypernym,
+    /// The other synset is more specific.
+    Hyponym,
+    /// The other synset is an instance of this class.
+    InstanceHyponym,
+    /// The words have opposite meanings.
+    Antonym,
+    /// A satellite adjective and its head adjective.
+    SimilarTo,
+    /// The noun an adjective pertains to, or the adjective an adverb is
+    /// derived from.
+    Pertainym,
+    /// Words of different parts of speech with the same root, like
+    /// "anxious" and "anxiety".
+    DerivationallyRelated,
+    /// Any other relation, by its pointer symbol.
+    Other(String),
+}
+
+impl Relation {
+    fn from_symbol(symbol: &str) -> Relation {
+        match symbol {
+            "@" => Relation::Hypernym,
+            "@i" => Relation::InstanceHypernym,
+            "~" => Relation::Hyponym,
+            "~i" => Relation::InstanceHyponym,
+            "!" => Relation::Antonym,
+            "&" => Relation::SimilarTo,
+            "\\" => Relation::Pertainym,
+            "+" => Relation::DerivationallyRelated,
+            symbol => Relation::Other(symbol.to_string()),
+        }
+    }
+}
+
+/// A relation to another synset.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pointer {
+    pub relation: Relation,
+    /// Offset of the other synset in the data file of its part of speech.
+    pub offset: usize,
+    /// Part of speech of the other synset, one of `NOUN`, `VERB`, `ADJ`
+    /// and `ADV`.
+    pub part: usize,
+    /// For relations between words rather than whole synsets, like
+    /// antonyms, the number of the word in this synset and in the other
+    /// one, starting at 1. Both are 0 for relations between synsets.
+    pub source: usize,
+    pub target: usize,
+}
+
+/// A set of synonyms: words sharing one meaning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Synset {
+    /// Offset of the synset in the data file of its part of speech, which
+    /// identifies it.
+    pub offset: usize,
+    /// Part of speech, one of `NOUN`, `VERB`, `ADJ` and `ADV`.
+    pub part: usize,
+    /// Lexicographer file the synset is from, like "noun.substance", see
+    /// [`LEXICOGRAPHER_FILES`](constant.LEXICOGRAPHER_FILES.html).
+    pub lexicographer_file: &'static str,
+    /// The words of the synset, lowercased like in the index files, with
+    /// collocations joined with underscores.
+    pub lemmas: Vec<String>,
+    pub pointers: Vec<Pointer>,
+    /// Definition and example sentences.
+    pub gloss: String,
+}
+
+fn invalid_data(reason: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, format!("malformed synset: {}", reason))
+}
+
+fn part_of_speech(ss_type: &str) -> io::Result<usize> {
+    match ss_type {
+        "n" => Ok(NOUN),
+        "v" => Ok(VERB),
+        // satellite adjectives are adjectives too
+        "a" | "s" => Ok(ADJ),
+        "r" => Ok(ADV),
+        _ => Err(invalid_data(&format!("unknown part of speech {}", ss_type))),
+    }
+}
+
+fn number(field: Option<&str>, radix: u32, name: &str) -> io::Result<usize> {
+    field
+        .and_then(|field| usize::from_str_radix(field, radix).ok())
+        .ok_or_else(|| invalid_data(&format!("expected the {}", name)))
+}
+
+impl Synset {
+    /// Parses a line of a `data.*` file.
+    pub fn parse(line: &str) -> io::Result<Synset> {
+        let (fields, gloss) = match line.find(" | ") {
+            Some(bar) => (&line[..bar], line[bar + 3..].trim_right()),
+            None => (line.trim_right(), ""),
+        };
+
+        let mut fields = fields.split(' ');
+
+        let offset = number(fields.next(), 10, "synset offset")?;
+        let lexicographer_file = LEXICOGRAPHER_FILES
+            .get(number(fields.next(), 10, "lexicographer file")?)
+            .ok_or_else(|| invalid_data("unknown lexicographer file"))?;
+        let part = part_of_speech(fields.next().unwrap_or(""))?;
+
+        let words = number(fields.next(), 16, "word count")?;
+        let mut lemmas = Vec::new();
+
+        for _ in 0..words {
+            let word = fields.next().ok_or_else(|| invalid_data("expected a word"))?;
+            // adjectives may be marked with their syntactic position, like "outback(a)"
+            let word = word.split('(').next().unwrap();
+
+            lemmas.push(word.to_lowercase());
+            number(fields.next(), 16, "lexical id")?;
+        }
+
+        let count = number(fields.next(), 10, "pointer count")?;
+        let mut pointers = Vec::new();
+
+        for _ in 0..count {
+            let relation = Relation::from_symbol(fields.next().ok_or_else(|| invalid_data("expected a pointer"))?);
+            let offset = number(fields.next(), 10, "pointer offset")?;
+            let part = part_of_speech(fields.next().unwrap_or(""))?;
+
+            let words = fields.next().unwrap_or("");
+            if words.len() != 4 {
+                return Err(invalid_data("expected the source and target of a pointer"));
+            }
+
+            pointers.push(Pointer {
+                relation,
+                offset,
+                part,
+                source: number(Some(&words[..2]), 16, "pointer source")?,
+                target: number(Some(&words[2..]), 16, "pointer target")?,
+            });
+        }
+
+        // verb frames follow, which are not kept
+
+        Ok(Synset {
+            offset,
+            part,
+            lexicographer_file,
+            lemmas,
+            pointers,
+            gloss: gloss.to_string(),
+        })
+    }
+
+    /// The pointers of the synset with `relation`.
+    pub fn related(&self, relation: Relation) -> Vec<&Pointer> {
+        self.pointers.iter().filter(|pointer| pointer.relation == relation).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Relation, Synset};
+    use super::super::wordnet_stemmer::{NOUN, VERB, ADJ};
+
+    #[test]
+    fn verb_synsets_are_parsed() {
+        let synset = Synset::parse("00004227 29 v 03 exhale 0 expire 0 breathe_out 0 008 @ 00001740 v 0000 \
+                                    + 03122260 a 0201 + 00836928 n 0202 + 14866261 n 0102 + 00836928 n 0101 \
+                                    ! 00005041 v 0101 ~ 00006523 v 0000 ~ 00007012 v 0000 02 + 02 00 + 08 00 \
+                                    | expel air; \"Exhale when you lift the weight\"  ")
+            .unwrap();
+
+        assert_eq!(synset.offset, 4227);
+        assert_eq!(synset.part, VERB);
+        assert_eq!(synset.lexicographer_file, "verb.body");
+        assert_eq!(synset.lemmas, vec!["exhale", "expire", "breathe_out"]);
+        assert_eq!(synset.gloss, "expel air; \"Exhale when you lift the weight\"");
+        assert_eq!(synset.pointers.len(), 8);
+
+        let hypernyms = synset.related(Relation::Hypernym).iter().map(|pointer| pointer.offset).collect::<Vec<usize>>();
+        assert_eq!(hypernyms, vec![1740]);
+
+        let antonym = synset.related(Relation::Antonym)[0];
+        assert_eq!((antonym.offset, antonym.part, antonym.source, antonym.target), (5041, VERB, 1, 1));
+
+        assert_eq!(synset.pointers[2].relation, Relation::DerivationallyRelated);
+        assert_eq!(synset.pointers[2].part, NOUN);
+    }
+
+    #[test]
+    fn adjective_markers_are_dropped() {
+        let synset = Synset::parse("00020141 00 s 02 outback(a) 0 remote 0 003 & 00019912 a 0000 \
+                                    + 05092372 n 0202 + 08522131 n 0101 | inaccessible and sparsely populated  ")
+            .unwrap();
+
+        assert_eq!(synset.part, ADJ);
+        assert_eq!(synset.lemmas, vec!["outback", "remote"]);
+        assert_eq!(synset.related(Relation::SimilarTo).len(), 1);
+        assert_eq!(synset.related(Relation::DerivationallyRelated).len(), 2);
+        assert!(Synset::parse("00020141 00 s 02 outback(a) 0").is_err());
+    }
+}
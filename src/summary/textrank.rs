@@ -0,0 +1,131 @@
+use std::collections::{HashMap, HashSet};
+
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const CONVERGENCE: f64 = 1e-6;
+
+/*
+    TextRank (Mihalcea & Tarau, 2004) over phrases given as lemmas.
+
+    Two phrases are connected by an edge weighted with their similarity
+
+        |shared lemmas| / (ln |lemmas of a| + ln |lemmas of b|)
+
+    and phrase weights are computed by weighted PageRank:
+
+        WS(a) = (1 - d) + d * sum over b of w(b, a) / sum over c of w(b, c) * WS(b)
+
+    iterated until no weight changes by more than CONVERGENCE.
+*/
+pub fn rank(cut_phrases: &Vec<Vec<&str>>) -> Vec<f64> {
+    let edges = similarities(cut_phrases);
+
+    let out_weights = edges
+        .iter()
+        .map(|neighbours| neighbours.iter().map(|&(_, weight)| weight).sum::<f64>())
+        .collect::<Vec<f64>>();
+
+    let mut scores = vec![1.0f64; cut_phrases.len()];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = vec![1.0 - DAMPING; scores.len()];
+
+        for (b, neighbours) in edges.iter().enumerate() {
+            if out_weights[b] == 0.0 {
+                continue;
+            }
+
+            for &(a, weight) in neighbours.iter() {
+                next[a] = next[a] + DAMPING * weight / out_weights[b] * scores[b];
+            }
+        }
+
+        let delta = scores
+            .iter()
+            .zip(next.iter())
+            .map(|(old, new)| (old - new).abs())
+            .fold(0.0, f64::max);
+
+        scores = next;
+
+        if delta < CONVERGENCE {
+            break;
+        }
+    }
+
+    scores
+}
+
+/* adjacency lists: for every phrase, (other phrase, similarity) with similarity > 0 */
+fn similarities(cut_phrases: &Vec<Vec<&str>>) -> Vec<Vec<(usize, f64)>> {
+    let lemma_sets = cut_phrases
+        .iter()
+        .map(|phrase| phrase.iter().cloned().collect::<HashSet<&str>>())
+        .collect::<Vec<HashSet<&str>>>();
+
+    /* index lemma -> phrases, so only phrases sharing lemmas are compared */
+    let mut phrases_by_lemma: HashMap<&str, Vec<usize>> = HashMap::new();
+
+    for (i, lemmas) in lemma_sets.iter().enumerate() {
+        for lemma in lemmas.iter() {
+            phrases_by_lemma.entry(lemma).or_insert(Vec::new()).push(i);
+        }
+    }
+
+    lemma_sets
+        .iter()
+        .enumerate()
+        .map(|(a, lemmas)| {
+            let mut shared: HashMap<usize, usize> = HashMap::new();
+
+            for lemma in lemmas.iter() {
+                for &b in phrases_by_lemma[lemma].iter() {
+                    if b != a {
+                        *shared.entry(b).or_insert(0) += 1;
+                    }
+                }
+            }
+
+            let mut neighbours = shared
+                .into_iter()
+                .filter_map(|(b, count)| {
+                    let norm = (lemmas.len() as f64).ln() + (lemma_sets[b].len() as f64).ln();
+
+                    // two single lemma phrases have no meaningful similarity
+                    if norm > 0.0 {
+                        Some((b, count as f64 / norm))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<(usize, f64)>>();
+
+            // fixed summation order, so results do not depend on hashing
+            neighbours.sort_by(|x, y| x.0.cmp(&y.0));
+
+            neighbours
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::rank;
+
+    #[test]
+    fn central_phrases_rank_highest() {
+        let phrases = vec![
+            vec!["water", "mars", "life"],
+            vec!["water", "mars"],
+            vec!["mars", "life", "water", "search"],
+            vec!["telescope", "orbit"],
+        ];
+
+        let scores = rank(&phrases);
+
+        assert!(scores[2] > scores[1]);
+        assert!(scores[1] > scores[3]);
+        // a phrase sharing nothing keeps the minimum weight
+        assert!((scores[3] - (1.0 - super::DAMPING)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,206 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use super::digest::Topic;
+
+/* concepts closer to the root of the hierarchy, like "abstraction" or "physical object", say nothing about a text */
+const MIN_TOPIC_DEPTH: usize = 4;
+
+/* the support of a keyword for its ancestors halves with every step up */
+const TOPIC_DECAY: f64 = 0.5;
+
+/* a noun synset, as far as topics are concerned */
+pub struct Concept {
+    pub label: String,
+    pub hypernyms: Vec<usize>,
+}
+
+/* a keyword and the synset of its most frequent sense */
+pub struct Sense<'a> {
+    pub word: &'a str,
+    pub score: f64,
+    pub synset: usize,
+}
+
+/* loads every concept once, `lookup` returns None for unreadable synsets */
+struct Hierarchy<F> {
+    lookup: F,
+    concepts: HashMap<usize, Option<Concept>>,
+    depths: HashMap<usize, usize>,
+}
+
+impl<F: FnMut(usize) -> Option<Concept>> Hierarchy<F> {
+    fn hypernyms(&mut self, synset: usize) -> Vec<usize> {
+        if !self.concepts.contains_key(&synset) {
+            let concept = (self.lookup)(synset);
+            self.concepts.insert(synset, concept);
+        }
+
+        self.concepts[&synset].as_ref().map(|concept| concept.hypernyms.clone()).unwrap_or(Vec::new())
+    }
+
+    /* length of the shortest hypernym chain to a root */
+    fn depth(&mut self, synset: usize) -> usize {
+        if let Some(&depth) = self.depths.get(&synset) {
+            return depth;
+        }
+
+        // guards against cycles in a broken dictionary
+        self.depths.insert(synset, 0);
+
+        let depth = self.hypernyms(synset)
+            .into_iter()
+            .map(|hypernym| self.depth(hypernym) + 1)
+            .min()
+            .unwrap_or(0);
+
+        self.depths.insert(synset, depth);
+        depth
+    }
+}
+
+/*
+    Topic labels: the hypernyms (and their hypernyms, up to the root) of
+    the keywords, scored by the keywords leading to them:
+
+        sum over the keywords of score * TOPIC_DECAY ^ (steps up - 1)
+
+    relative to the total score of the keywords, so 1 is a hypernym of
+    every keyword.
+
+    Only concepts at least MIN_TOPIC_DEPTH steps below a root and shared
+    by at least two keywords are labels, and of labels shared by the same
+    keywords only the most specific one. Best first, then the most
+    specific, then alphabetically.
+*/
+pub fn topics<F>(senses: &[Sense], lookup: F) -> Vec<Topic>
+    where F: FnMut(usize) -> Option<Concept>
+{
+    let mut hierarchy = Hierarchy { lookup, concepts: HashMap::new(), depths: HashMap::new() };
+
+    let mut support: HashMap<usize, f64> = HashMap::new();
+    let mut supporters: HashMap<usize, Vec<&str>> = HashMap::new();
+
+    for sense in senses.iter() {
+        // breadth first, so every ancestor is reached by its shortest chain
+        let mut seen = HashSet::new();
+        let mut level = vec![sense.synset];
+        let mut steps = 0;
+
+        seen.insert(sense.synset);
+
+        while !level.is_empty() {
+            let mut next = Vec::new();
+
+            for synset in level {
+                for hypernym in hierarchy.hypernyms(synset) {
+                    if seen.insert(hypernym) {
+                        next.push(hypernym);
+                    }
+                }
+            }
+
+            steps = steps + 1;
+
+            for &ancestor in next.iter() {
+                *support.entry(ancestor).or_insert(0.0) += sense.score * TOPIC_DECAY.powi(steps - 1);
+                supporters.entry(ancestor).or_insert(Vec::new()).push(sense.word);
+            }
+
+            level = next;
+        }
+    }
+
+    let total = senses.iter().map(|sense| sense.score).sum::<f64>();
+
+    let mut candidates = Vec::new();
+
+    for (&synset, &support) in support.iter() {
+        let depth = hierarchy.depth(synset);
+
+        if supporters[&synset].len() < 2 || depth < MIN_TOPIC_DEPTH {
+            continue;
+        }
+
+        let label = match hierarchy.concepts[&synset] {
+            Some(ref concept) => concept.label.clone(),
+            None => continue,
+        };
+
+        candidates.push((depth, Topic {
+            label,
+            score: support / total,
+            keywords: supporters[&synset].iter().map(|word| word.to_string()).collect(),
+        }));
+    }
+
+    candidates.sort_by(|&(a_depth, ref a), &(b_depth, ref b)| {
+        b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal)
+            .then(b_depth.cmp(&a_depth))
+            .then(a.label.cmp(&b.label))
+    });
+
+    let mut topics: Vec<Topic> = Vec::new();
+
+    for (_, topic) in candidates {
+        if topics.iter().all(|other| other.keywords != topic.keywords) {
+            topics.push(topic);
+        }
+    }
+
+    topics
+}
+
+#[cfg(test)]
+mod test {
+    use super::{topics, Concept, Sense};
+
+    /*
+        0 entity
+        1   physical entity
+        2     matter
+        3       substance
+        4         drug
+        5           stimulant
+        6             caffeine
+        7             nicotine
+        8           sedative
+        9             alcohol
+    */
+    fn lookup(synset: usize) -> Option<Concept> {
+        let (label, hypernyms) = match synset {
+            0 => ("entity", vec![]),
+            1 => ("physical entity", vec![0]),
+            2 => ("matter", vec![1]),
+            3 => ("substance", vec![2]),
+            4 => ("drug", vec![3]),
+            5 => ("stimulant", vec![4]),
+            6 => ("caffeine", vec![5]),
+            7 => ("nicotine", vec![5]),
+            8 => ("sedative", vec![4]),
+            9 => ("alcohol", vec![8]),
+            _ => return None,
+        };
+
+        Some(Concept { label: label.to_string(), hypernyms })
+    }
+
+    #[test]
+    fn shared_ancestors_are_labels() {
+        let senses = vec![
+            Sense { word: "caffeine", score: 4.0, synset: 6 },
+            Sense { word: "nicotine", score: 2.0, synset: 7 },
+            Sense { word: "alcohol", score: 2.0, synset: 9 },
+        ];
+
+        let topics = topics(&senses, lookup);
+
+        let labels = topics.iter().map(|topic| topic.label.as_str()).collect::<Vec<&str>>();
+
+        // "sedative" only leads to one keyword, "substance" and above are too general
+        assert_eq!(labels, vec!["stimulant", "drug"]);
+        assert_eq!(topics[0].score, 6.0 / 8.0);
+        assert_eq!(topics[0].keywords, vec!["caffeine", "nicotine"]);
+        assert_eq!(topics[1].score, (2.0 + 1.0 + 1.0) / 8.0);
+    }
+}
@@ -1,8 +1,12 @@
 use std::collections::{HashMap, HashSet, hash_map};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, ErrorKind, Result};
+use std::io::{self, BufRead, BufReader, ErrorKind, Result, Seek, SeekFrom};
+use std::path::Path;
 use std::vec::Vec;
 
+use super::categories::{lexicographer_pointers, lexicographer_words, read_lexicographer_files};
+use super::synset::{Pointer, Relation, Synset, LEXICOGRAPHER_FILES};
+
 
 #[derive(PartialEq)]
 pub enum Part {
@@ -47,6 +51,10 @@ const WN_FILES: [[&'static str; 2]; 4] = [/* noun */
                                           ["index.adj", "adj.exc"],
                                           /* adv  */
                                           ["index.adv", "adv.exc"]];
+const DATA_FILES: [&'static str; 4] = ["data.noun", "data.verb", "data.adj", "data.adv"];
+const PART_NAMES: [&'static str; 4] = ["nouns", "verbs", "adjectives", "adverbs"];
+/* the part of speech in sense keys, like the 1 of "person%1:03:00::" */
+const SENSE_KEY_TYPES: [u32; 4] = [1, 2, 3, 4];
 
 
 /*static STR_ADJ: char = 'a';
@@ -60,6 +68,8 @@ type Wordlist = Vec<FastHashMap>;
 type Exceptions = Vec<HashMap<String, Vec<String>>>;
 type Substitutions = Vec<Vec<Vec<&'static str>>>;
 type LemmaPosOffsetMap = HashMap<String, HashMap<usize, Vec<i32>>>;
+/* the senses of the words of one part of speech, see compile_lemma_pos_offset_map */
+type Senses = HashMap<String, Vec<i32>>;
 type FileMap = HashMap<char, String>;
 
 #[derive(Clone,Debug)]
@@ -68,7 +78,18 @@ pub struct WordnetStemmer {
     pub exceptions: Exceptions,
     pub substitutions: Substitutions,
     pub lemma_pos_offset_map: LemmaPosOffsetMap,
+    /* senses of the compiled parts of speech, numbered in order: they are no offsets, see compile_lemma_pos_offset_map */
+    pub compiled_senses: LemmaPosOffsetMap,
+    /* per part of speech without a data file the synsets compiled with compiled_senses, by number */
+    pub compiled_synsets: Vec<Vec<Synset>>,
+    /* number of words of the longest collocation, like "blood_pressure" */
+    pub collocation_words: usize,
     pub basedir: String,
+    /* parts of speech without an index file, compiled from the lexicographer files or without any words */
+    pub compiled: Vec<usize>,
+    pub unavailable: Vec<usize>,
+    /* what is missing from the dictionary, see load */
+    pub warnings: Vec<String>,
 }
 
 impl WordnetStemmer {
@@ -112,16 +133,27 @@ impl WordnetStemmer {
                 vec![],
             ],
             lemma_pos_offset_map: HashMap::new(),
+            compiled_senses: HashMap::new(),
+            compiled_synsets: Vec::new(),
+            collocation_words: 1,
+            compiled: Vec::new(),
+            unavailable: Vec::new(),
+            warnings: Vec::new(),
         };
 
         for _ in PARTS.iter() {
             wn.wordlist.push(Default::default());
             wn.exceptions.push(Default::default());
+            wn.compiled_synsets.push(Vec::new());
         }
-        for part in PARTS.iter() {
-            try!(wn.load(*part, WN_FILES[*part]));
+        for variant in [Part::Noun, Part::Verb, Part::Adj, Part::Adv].iter() {
+            wn.load(variant)?;
         }
 
+        if wn.unavailable.len() == PARTS.len() {
+            return Err(io::Error::new(ErrorKind::NotFound,
+                                      format!("WordnetStemmer: no index files or lexicographer files in {}", basedir)));
+        }
 
         Ok(wn)
     }
@@ -136,116 +168,259 @@ impl WordnetStemmer {
         fm
     }*/
 
-    fn load(&mut self, part: usize, pair: [&str; 2]) -> Result<()> {
-        let fname: String = format!("{}{}", self.basedir, pair[0]);
-        let mut f = match File::open(fname.clone()) {
-            Ok(v) => v,
-            Err(e) => {
-                match e.kind() {
-                    ErrorKind::NotFound => {
-                        return Err(io::Error::new(ErrorKind::Other,
-                                                  format!("WordnetStemmer: could not open or read file {}", fname)))
+    /*
+        Reads the words of a part of speech from its index file or, if the
+        dictionary has none, compiles them from the lexicographer files in
+        dbfiles/. Parts of speech missing either are left empty, like those
+        without an exception or a data file, with a warning. Without a data
+        file the hypernyms of the synsets are compiled too, if there are
+        lexicographer files.
+    */
+    fn load(&mut self, variant: &Part) -> Result<()> {
+        let part = variant.as_usize();
+        let basedir = Path::new(&self.basedir).to_path_buf();
+
+        match File::open(basedir.join(WN_FILES[part][0])) {
+            Ok(f) => self.load_lemma_pos_offset_map(part, BufReader::new(f))?,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {
+                let (senses, synsets) = self.compile_lemma_pos_offset_map(variant)?;
+
+                if senses.is_empty() {
+                    self.unavailable.push(part);
+                    self.warnings.push(format!("{} is missing and there are no dbfiles/{}.* to compile it from: {} are unavailable",
+                                               WN_FILES[part][0], variant.as_str(), PART_NAMES[part]));
+                } else {
+                    self.compiled.push(part);
+                    self.warnings.push(format!("{} is missing, compiled it from dbfiles/{}.*: the synsets of {} are unavailable",
+                                               WN_FILES[part][0], variant.as_str(), PART_NAMES[part]));
+                }
+
+                // there are no offsets to look synsets up by
+                for (lemma, synsets) in senses {
+                    self.insert(part, lemma.clone(), Vec::new());
+                    self.compiled_senses.entry(lemma).or_insert(HashMap::new()).insert(part, synsets);
+                }
+
+                self.compiled_synsets[part] = synsets;
+            }
+            Err(e) => return Err(e),
+        }
+
+        match File::open(basedir.join(WN_FILES[part][1])) {
+            Ok(f) => {
+                for line_result in BufReader::new(f).lines() {
+                    let line: String = line_result?;
+                    if line.starts_with("  ") {
+                        continue;
                     }
-                    _ => return Err(e),
+
+                    let words: Vec<&str> = line.splitn(3, ' ').collect();
+                    self.exceptions[part]
+                        .entry(words[0].to_owned())
+                        .or_insert(Vec::new())
+                        .push(words[1].to_owned());
                 }
             }
-        };
-        let mut br = BufReader::new(f);
-        for line_result in br.lines() {
-            let line = try!(line_result);
-            if line.starts_with("  ") {
-                continue;
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {
+                self.warnings.push(format!("{} is missing: irregular {} are not lemmatized", WN_FILES[part][1], PART_NAMES[part]));
             }
+            Err(e) => return Err(e),
+        }
+
+        // compiled and unavailable parts of speech were warned about already
+        if self.has_data_file(part) && !basedir.join(DATA_FILES[part]).is_file() {
+            let (senses, synsets) = self.compile_lemma_pos_offset_map(variant)?;
+
+            if synsets.is_empty() {
+                self.warnings.push(format!("{} is missing: the synsets of {} are unavailable", DATA_FILES[part], PART_NAMES[part]));
+            } else {
+                self.warnings.push(format!("{} is missing, compiled the hypernyms of {} from dbfiles/{}.*: their other relations are unavailable",
+                                           DATA_FILES[part], PART_NAMES[part], variant.as_str()));
 
-            let word = line.splitn(2, ' ').nth(0).unwrap();
-            self.wordlist[part].insert(word.clone().to_owned(), word.to_owned());
+                for (lemma, synsets) in senses {
+                    self.compiled_senses.entry(lemma).or_insert(HashMap::new()).insert(part, synsets);
+                }
+
+                self.compiled_synsets[part] = synsets;
+            }
         }
 
+        Ok(())
+    }
 
-        let fname = format!("{}{}", self.basedir, pair[1]);
-        f = try!(File::open(fname));
-        br = BufReader::new(f);
+    fn load_lemma_pos_offset_map(&mut self, part: usize, br: BufReader<File>) -> Result<()> {
         for line_result in br.lines() {
-            let line: String = try!(line_result);
-            if line.starts_with("  ") {
+            let line = try!(line_result);
+            if line.starts_with(" ") {
                 continue;
             }
-
-            let words: Vec<&str> = line.splitn(3, ' ').collect();
-            self.exceptions[part]
-                .entry(words[0].to_owned())
-                .or_insert(Vec::new())
-                .push(words[1].to_owned());
+            let mut iter = line.split(' ');
+
+            // get the lemma and part-of-speech
+            let lemma = iter.next().unwrap();
+            let _ = iter.next().unwrap(); //pos
+            let n_synsets = iter.next().unwrap().parse::<i32>().unwrap();
+            // assert!(n_synsets > 0)
+
+            let n_pointers = iter.next().unwrap().parse::<i32>().unwrap();
+
+            // same as number of synsets
+            let _ = iter.nth((n_pointers as usize))
+                .unwrap()
+                .parse::<i32>()
+                .unwrap(); //n_senses
+
+            // get number of senses ranked according to frequency
+            let _ = iter.next();
+            // get synset offsets
+            let synset_offsets: Vec<i32> = iter.take(n_synsets as usize)
+                .map(|x| x.parse::<i32>().unwrap())
+                .collect();
+
+            self.insert(part, lemma.to_owned(), synset_offsets);
         }
-
-        let res = self.load_lemma_pos_offset_map();
-        res
+        Ok(())
     }
 
-    fn load_lemma_pos_offset_map(&mut self) -> Result<()> {
-        for variant in [Part::Noun, Part::Verb, Part::Adj, Part::Adv].iter() {
-            //for suffix in WordnetStemmer::filemap().values(){
-            let fname: String = format!("{}/index.{}", self.basedir, variant.as_str());
-            let f = match File::open(fname.clone()) {
-                Ok(v) => v,
-                Err(e) => {
-                    match e.kind() {
-                        ErrorKind::NotFound => {
-                            return Err(io::Error::new(ErrorKind::Other,
-                                                      format!("WordnetStemmer: could not open or read file {}", fname)))
-                        }
-                        _ => return Err(e),
+    /*
+        The senses of every word of a part of speech in the lexicographer
+        files, like the index file the grinder would write for them, most
+        frequent sense first as counted in cntlist (if there is one).
+
+        The synsets are numbered in order rather than by their offset in the
+        data file, which would have to be compiled too, so the numbers only
+        tell the senses of these words apart and are kept out of
+        lemma_pos_offset_map. The synsets themselves come with their words
+        and their hypernym pointers, by number too, but without any other
+        pointers or a gloss.
+    */
+    fn compile_lemma_pos_offset_map(&self, variant: &Part) -> Result<(Senses, Vec<Synset>)> {
+        let basedir = Path::new(&self.basedir);
+        let prefix = format!("{}.", variant.as_str());
+
+        // sense keys look like "person%1:03:00::", with the number of the sense in the index file
+        let mut ranks: HashMap<String, u32> = HashMap::new();
+
+        match File::open(basedir.join("cntlist")) {
+            Ok(f) => {
+                for line_result in BufReader::new(f).lines() {
+                    let line = line_result?;
+                    let fields = line.split(' ').collect::<Vec<&str>>();
+
+                    if let (Some(key), Some(Ok(rank))) = (fields.get(1), fields.get(2).map(|rank| rank.parse::<u32>())) {
+                        ranks.insert(key.to_string(), rank);
                     }
                 }
-            };
-            let br = BufReader::new(f);
-            for line_result in br.lines() {
-                let line = try!(line_result);
-                if line.starts_with(" ") {
-                    continue;
-                }
-                let mut iter = line.split(' ');
-
-                // get the lemma and part-of-speech
-                let lemma = iter.next().unwrap();
-                let _ = iter.next().unwrap(); //pos
-                let n_synsets = iter.next().unwrap().parse::<i32>().unwrap();
-                // assert!(n_synsets > 0)
-
-                let n_pointers = iter.next().unwrap().parse::<i32>().unwrap();
-
-                // same as number of synsets
-                let _ = iter.nth((n_pointers as usize))
-                    .unwrap()
-                    .parse::<i32>()
-                    .unwrap(); //n_senses
-
-                // get number of senses ranked according to frequency
-                let _ = iter.next();
-                // get synset offsets
-                let synset_offsets: Vec<i32> = iter.take(n_synsets as usize)
-                    .map(|x| x.parse::<i32>().unwrap())
-                    .collect();
-                match self.lemma_pos_offset_map.entry(lemma.to_owned()) {
-                    hash_map::Entry::Vacant(entry) => {
-                        let mut hm = HashMap::new();
-                        hm.insert(variant.as_usize(), synset_offsets);
-                        entry.insert(hm);
-                    }
-                    hash_map::Entry::Occupied(mut entry) => {
-                        entry.get_mut().insert(variant.as_usize(), synset_offsets);
-                    }
-                }
-                //let mut hm = HashMap::new();
-                //hm.insert(variant.as_usize(), synset_offsets);
-                //self.lemma_pos_offset_map.insert(lemma.to_owned(), hm);
-                if *variant == Part::Adj {}
+            }
+            Err(ref e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
 
+        let part = variant.as_usize();
+        let mut senses: HashMap<String, Vec<(u32, i32)>> = HashMap::new();
+        let mut synsets = Vec::new();
+        let mut synset = 0;
 
+        // pointers name a word of the synset by its file and lexical id, like "noun.Tops:feeling,@"
+        let mut numbers: HashMap<(usize, String, u32), i32> = HashMap::new();
+        let mut hypernyms = Vec::new();
+
+        read_lexicographer_files(&basedir.join("dbfiles"), &[&prefix], |number, line| {
+            let words = lexicographer_words(line);
+
+            if words.is_empty() {
+                return;
             }
 
+            let mut lemmas: Vec<String> = Vec::new();
+
+            for (lemma, lex_id) in words {
+                let key = format!("{}%{}:{:02}:{:02}::", lemma, SENSE_KEY_TYPES[part], number, lex_id);
+                let rank = ranks.get(&key).cloned().unwrap_or(u32::max_value());
+
+                numbers.insert((number, lemma.clone(), lex_id), synset);
+
+                // a word listed twice in a synset is one sense
+                if !lemmas.contains(&lemma) {
+                    lemmas.push(lemma.clone());
+                    senses.entry(lemma).or_insert(Vec::new()).push((rank, synset));
+                }
+            }
+
+            hypernyms.push(lexicographer_pointers(line)
+                .into_iter()
+                .filter_map(|(file, (lemma, lex_id), symbol)| {
+                    let relation = match symbol {
+                        "@" => Relation::Hypernym,
+                        "@i" => Relation::InstanceHypernym,
+                        _ => return None,
+                    };
+
+                    Some((relation, (file.unwrap_or(number), lemma, lex_id)))
+                })
+                .collect::<Vec<_>>());
+
+            synsets.push(Synset {
+                offset: synset as usize,
+                part,
+                lexicographer_file: LEXICOGRAPHER_FILES[number],
+                lemmas,
+                pointers: Vec::new(),
+                gloss: String::new(),
+            });
+
+            synset = synset + 1;
+        })?;
+
+        // pointers into files of other parts of speech or to missing words are dropped
+        for (synset, hypernyms) in synsets.iter_mut().zip(hypernyms) {
+            synset.pointers = hypernyms
+                .into_iter()
+                .filter_map(|(relation, key)| numbers.get(&key).map(|&number| Pointer {
+                    relation,
+                    offset: number as usize,
+                    part,
+                    source: 0,
+                    target: 0,
+                }))
+                .collect();
         }
-        Ok(())
+
+        // senses without a count keep the order of the files
+        let senses = senses
+            .into_iter()
+            .map(|(lemma, mut offsets)| {
+                offsets.sort_by_key(|&(rank, _)| rank);
+                (lemma, offsets.into_iter().map(|(_, synset)| synset).collect())
+            })
+            .collect();
+
+        Ok((senses, synsets))
+    }
+
+    fn insert(&mut self, part: usize, lemma: String, synset_offsets: Vec<i32>) {
+        let words = lemma.split('_').count();
+        if words > self.collocation_words {
+            self.collocation_words = words;
+        }
+
+        self.wordlist[part].insert(lemma.clone(), lemma.clone());
+
+        match self.lemma_pos_offset_map.entry(lemma) {
+            hash_map::Entry::Vacant(entry) => {
+                let mut hm = HashMap::new();
+                hm.insert(part, synset_offsets);
+                entry.insert(hm);
+            }
+            hash_map::Entry::Occupied(mut entry) => {
+                entry.get_mut().insert(part, synset_offsets);
+            }
+        }
+    }
+
+    /* whether `part` has offsets, those of its data file */
+    pub(crate) fn has_data_file(&self, part: usize) -> bool {
+        !self.compiled.contains(&part) && !self.unavailable.contains(&part)
     }
 
     fn apply_rules(&self, part: usize, words: &Vec<String>) -> Vec<String> {
@@ -338,6 +513,96 @@ impl WordnetStemmer {
             .join(" ")
     }
 
+    /*
+        The collocation made of `words` (already lowercased), like
+        "nervous_system" for ["nervous", "systems"], if WordNet knows it.
+    */
+    pub fn collocation(&self, words: &[&str]) -> Option<String> {
+        if words.len() < 2 {
+            return None;
+        }
+
+        let joined = words.join("_");
+
+        PARTS
+            .iter()
+            .map(|&part| self.morphy(part, &joined))
+            .find(|lemmas| lemmas.len() > 0)
+            .map(|lemmas| lemmas.into_iter().min_by_key(|lemma| lemma.len()).unwrap())
+    }
+
+    /* whether the synsets of `part` can be read, see synset */
+    pub fn has_synsets(&self, part: usize) -> bool {
+        self.has_data_file(part) && Path::new(&self.basedir).join(DATA_FILES[part]).is_file()
+    }
+
+    /*
+        The senses of `lemma` as `part`, most frequent first, to tell whether
+        words share one: the offsets in the data file or, for compiled parts
+        of speech, the numbers of their synsets.
+    */
+    pub fn senses(&self, part: usize, lemma: &str) -> &[i32] {
+        let senses = if self.compiled.contains(&part) { &self.compiled_senses } else { &self.lemma_pos_offset_map };
+
+        senses.get(lemma).and_then(|parts| parts.get(&part)).map(|senses| senses.as_slice()).unwrap_or(&[])
+    }
+
+    /*
+        The synset at `offset` of the data file for `part`, i.e. one of the
+        offsets in lemma_pos_offset_map.
+    */
+    pub fn synset(&self, part: usize, offset: usize) -> Result<Synset> {
+        let mut file = self.open_data_file(part)?;
+
+        self.read_synset(&mut file, part, offset)
+    }
+
+    /* the synsets of `lemma` (like "blood_pressure") as `part`, most frequent sense first */
+    pub fn synsets(&self, part: usize, lemma: &str) -> Result<Vec<Synset>> {
+        let offsets = match self.lemma_pos_offset_map.get(lemma).and_then(|parts| parts.get(&part)) {
+            Some(offsets) => offsets,
+            None => return Ok(Vec::new()),
+        };
+
+        self.synsets_at(part, &offsets.iter().map(|&offset| offset as usize).collect::<Vec<usize>>())
+    }
+
+    /* the synsets at `offsets` of the data file for `part`, read in one go */
+    pub fn synsets_at(&self, part: usize, offsets: &[usize]) -> Result<Vec<Synset>> {
+        let mut file = self.open_data_file(part)?;
+
+        offsets
+            .iter()
+            .map(|&offset| self.read_synset(&mut file, part, offset))
+            .collect()
+    }
+
+    fn open_data_file(&self, part: usize) -> Result<BufReader<File>> {
+        if !self.has_data_file(part) {
+            return Err(io::Error::new(ErrorKind::NotFound,
+                                      format!("{} is missing, the {} were compiled without synsets", WN_FILES[part][0], PART_NAMES[part])));
+        }
+
+        Ok(BufReader::new(File::open(Path::new(&self.basedir).join(DATA_FILES[part]))?))
+    }
+
+    fn read_synset(&self, file: &mut BufReader<File>, part: usize, offset: usize) -> Result<Synset> {
+        let mut line = Vec::new();
+
+        file.seek(SeekFrom::Start(offset as u64))?;
+        file.read_until(b'\n', &mut line)?;
+
+        let synset = Synset::parse(&String::from_utf8_lossy(&line))
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, format!("{} offset {}: {}", DATA_FILES[part], offset, e)))?;
+
+        if synset.offset != offset {
+            return Err(io::Error::new(ErrorKind::InvalidData,
+                                      format!("{} offset {}: not the start of a synset", DATA_FILES[part], offset)));
+        }
+
+        Ok(synset)
+    }
+
     #[inline(always)]
     pub fn is_noun(&self, word: &str) -> bool {
         self.wordlist[NOUN].get(word).is_some()
@@ -368,7 +633,7 @@ impl WordnetStemmer {
 mod test {
     #[test]
     fn test_stemming() {
-        let wn = ::WordnetStemmer::new("/home/maciej/nltk_data/corpora/wordnet/").unwrap();
+        let wn = ::WordnetStemmer::new("tests/fixtures/dict/").unwrap();
         for (word, expected) in vec![
             ("dogs", "dog"),
             ("money", "money"),
@@ -377,7 +642,24 @@ mod test {
             ("press", "press"),
             ("ferries", "ferry"),
         ] {
-            assert_eq!(expected.to_owned(), wn.lemma(::NOUN, word.to_owned()) );
+            assert_eq!(expected.to_owned(), wn.lemma(::NOUN, word) );
         }
     }
+
+    #[test]
+    fn missing_indexes_are_compiled_from_lexicographer_files() {
+        // only the lexicographer files of two noun categories and a cntlist
+        let wn = ::WordnetStemmer::new("tests/fixtures/compiled-dict/").unwrap();
+
+        assert_eq!(wn.lemma(::NOUN, "fears"), "fear");
+        assert!(wn.is_noun("anxiousness") && !wn.is_verb("fear"));
+        // the sense in noun.state is the more frequent one
+        assert_eq!(wn.senses(::NOUN, "anxiety"), &[2, 0]);
+        assert!(wn.lemma_pos_offset_map["anxiety"][&::NOUN].is_empty());
+        assert!(wn.synset(::NOUN, 0).is_err());
+
+        assert_eq!((wn.compiled.clone(), wn.unavailable.clone()), (vec![::NOUN], vec![::VERB, ::ADJ, ::ADV]));
+        assert!(wn.warnings[0].starts_with("index.noun is missing, compiled it from dbfiles/noun.*"));
+        assert!(wn.warnings.iter().any(|warning| warning.ends_with("verbs are unavailable")));
+    }
 }
@@ -1,8 +1,10 @@
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng, XorShiftRng};
 
 /* trait on str allowing for case to be changed */
 use string_utils::StringCase;
 
+use error::SummaryError;
+
 const TITLE_NUM: u32 = 666;
 
 const TITLE_PARTS: [(&'static str, &'static str); 666] = [(r"Succeed With ", r" In 24 Hours"),
@@ -672,8 +674,37 @@ const TITLE_PARTS: [(&'static str, &'static str); 666] = [(r"Succeed With ", r"
                                                           (r"", r": Launching Your Own Affiliate Program"),
                                                           (r"6 Nontraditional ", r" Techniques That Are Unlike Any You've Ever Seen. Ther're Perfect.")];
 
-pub fn build_titles(keywords: &Vec<String>, num_titles: u32) -> Vec<String> {
-    let mut rng = thread_rng();
+/* FNV-1a over the keywords, so the same keywords always yield the same titles */
+fn seed(keywords: &Vec<String>) -> [u32; 4] {
+    let mut hash = 0xcbf29ce484222325u64;
+
+    for keyword in keywords.iter() {
+        // the separator keeps ["ab", "c"] and ["a", "bc"] apart
+        for byte in keyword.bytes().chain(Some(0u8)) {
+            hash = (hash ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    let (low, high) = (hash as u32, (hash >> 32) as u32);
+
+    // XorShiftRng rejects an all zero seed
+    [low, high, low ^ 0x9e3779b9, high ^ 0x7f4a7c15]
+}
+
+/// Builds `num_titles` titles, each around a keyword.
+///
+/// Titles are picked pseudo-randomly but deterministically: the same
+/// keywords (in the same order) always yield the same titles.
+///
+/// Fails with `SummaryError::NoKeywords` if titles are requested but
+/// `keywords` is empty.
+pub fn build_titles(keywords: &Vec<String>, num_titles: u32) -> Result<Vec<String>, SummaryError> {
+    // a title needs at least one keyword to be built around
+    if num_titles > 0 && keywords.is_empty() {
+        return Err(SummaryError::NoKeywords);
+    }
+
+    let mut rng = XorShiftRng::from_seed(seed(keywords));
 
     let mut out_titles = Vec::new();
 
@@ -705,5 +736,5 @@ pub fn build_titles(keywords: &Vec<String>, num_titles: u32) -> Vec<String> {
         out_titles.push(title);
     }
 
-    out_titles
+    Ok(out_titles)
 }
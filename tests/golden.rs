@@ -0,0 +1,236 @@
+/*
+    Golden file regression tests.
+
+    Every text in tests/fixtures/corpus is summarized with a handful of
+    option sets and the result is compared with tests/golden/<name>.txt.
+    A failure means summaries changed: if that is intended, regenerate the
+    files with
+
+        UPDATE_GOLDEN=1 cargo test --test golden
+
+    and review the diff along with the change.
+
+    tests/fixtures/dict is the subset of WordNet covering the words of the
+    corpus, so the tests do not depend on a locally installed dictionary.
+    It has no data files, and its dbfiles/ only the noun synsets leading
+    up from a few words of caffeine.txt, for topics.
+*/
+extern crate summary;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use summary::{titlegenerator, Algorithm, Digest, Length, Normalization, Order, Summary, SummaryOptions};
+
+const NUM_KEYWORDS: u32 = 8;
+const NUM_KEYPHRASES: u32 = 4;
+const NUM_TITLES: u32 = 2;
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+fn configurations() -> Vec<(&'static str, SummaryOptions)> {
+    let default = SummaryOptions {
+        num_keywords: Some(NUM_KEYWORDS),
+        num_keyphrases: Some(NUM_KEYPHRASES),
+        ..SummaryOptions::default()
+    };
+
+    vec![
+        ("frequency", default.clone()),
+        ("textrank", SummaryOptions { algorithm: Algorithm::TextRank, ..default.clone() }),
+        ("average", SummaryOptions { normalization: Normalization::Average, ..default.clone() }),
+        ("mmr", SummaryOptions { lambda: Some(0.5), ..default.clone() }),
+        ("max_words", SummaryOptions { length: Length::Words(60), ..default.clone() }),
+        ("score_order", SummaryOptions { order: Order::Score, ..default.clone() }),
+        ("position", SummaryOptions { lead_bias: 1.0, paragraph_bias: 0.5, ..default.clone() }),
+        ("query", SummaryOptions { query: Some("Is water good for the heart?".to_string()), ..default.clone() }),
+    ]
+}
+
+fn render(summary: &Summary, text: &str) -> String {
+    let mut out = String::new();
+
+    for (name, options) in configurations() {
+        let digest = summary.digest(text, &options);
+
+        out.push_str(&format!("== {}\n", name));
+
+        for phrase in digest.phrases.iter() {
+            out.push_str(&format!("phrase {} {:.4} {}\n", phrase.index, phrase.score, phrase.text));
+        }
+
+        for keyword in digest.keywords.iter() {
+            out.push_str(&format!("keyword {} {} {:.4}\n", keyword.word, keyword.frequency, keyword.score));
+        }
+
+        for keyphrase in digest.keyphrases.iter() {
+            out.push_str(&format!("keyphrase {} {} {:.4}\n", keyphrase.phrase, keyphrase.frequency, keyphrase.score));
+        }
+
+        let keywords = digest.keywords.into_iter().map(|keyword| keyword.word).collect();
+
+        for title in titlegenerator::build_titles(&keywords, NUM_TITLES).unwrap() {
+            out.push_str(&format!("title {}\n", title));
+        }
+    }
+
+    out
+}
+
+fn read(path: &Path) -> String {
+    let mut contents = String::new();
+
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .unwrap_or_else(|e| panic!("could not read {}: {}", path.display(), e));
+
+    contents
+}
+
+#[test]
+fn summaries_match_golden_files() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+    let update = env::var("UPDATE_GOLDEN").is_ok();
+
+    let mut corpus = fs::read_dir(fixtures().join("fixtures/corpus"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<PathBuf>>();
+
+    corpus.sort();
+
+    let mut mismatches = Vec::new();
+
+    for path in corpus.iter() {
+        let golden = fixtures().join("golden").join(path.file_name().unwrap());
+        let actual = render(&summary, &read(path));
+
+        if update {
+            File::create(&golden).and_then(|mut file| file.write_all(actual.as_bytes())).unwrap();
+            continue;
+        }
+
+        let expected = read(&golden);
+
+        if actual != expected {
+            let (line, (expected, actual)) = expected.lines()
+                .chain(Some("<end of file>"))
+                .zip(actual.lines().chain(Some("<end of file>")))
+                .enumerate()
+                .find(|&(_, (expected, actual))| expected != actual)
+                .unwrap();
+
+            mismatches.push(format!("{} line {}:\n  expected: {}\n  actual:   {}",
+                                    golden.display(), line + 1, expected, actual));
+        }
+    }
+
+    assert!(corpus.len() > 0, "no fixtures found");
+    assert!(mismatches.is_empty(),
+            "summaries differ from the golden files (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+            mismatches.join("\n"));
+}
+
+#[test]
+fn documents_are_summarized_without_repeating_phrases() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+
+    // four distinct sentences, two of them twice
+    let ties = read(&fixtures().join("fixtures/corpus/ties.txt"));
+
+    let options = SummaryOptions { length: Length::Phrases(4), ..SummaryOptions::default() };
+    let digest = summary.digest_documents(&[&ties, &ties], &options);
+
+    let mut texts = digest.phrases.iter().map(|phrase| phrase.text.as_str()).collect::<Vec<&str>>();
+    texts.sort();
+    texts.dedup();
+
+    assert_eq!(texts.len(), 4);
+
+    for phrase in digest.phrases.iter() {
+        // the first occurrence is kept
+        assert_eq!(phrase.document, 0);
+        assert_eq!(&ties[phrase.span.start..phrase.span.end], phrase.text);
+    }
+}
+
+#[test]
+fn collocations_are_single_keywords() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+
+    let text = "Microbes on the red planet. The red planet has microbes, red microbes.";
+    let digest = summary.digest(text, &SummaryOptions::default());
+
+    let keyword = digest.keywords.iter().find(|keyword| keyword.word == "red planet").unwrap();
+
+    assert_eq!(keyword.frequency, 2);
+
+    for &(_, span) in keyword.occurrences.iter() {
+        assert_eq!(&text[span.start..span.end], "red planet");
+    }
+
+    // "planet" only ever occurs as part of the collocation, "microbes, red" is not one
+    assert!(digest.keywords.iter().all(|keyword| keyword.word != "planet"));
+    assert!(digest.keywords.iter().any(|keyword| keyword.word == "microbe" && keyword.frequency == 3));
+}
+
+#[test]
+fn synonyms_are_counted_as_one_keyword() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+    let mars = read(&fixtures().join("fixtures/corpus/mars.txt"));
+
+    let separate = summary.digest(&mars, &SummaryOptions::default());
+    let merged = summary.digest(&mars, &SummaryOptions { merge_synonyms: true, ..SummaryOptions::default() });
+
+    let frequency = |digest: &Digest, word: &str| {
+        digest.keywords.iter().find(|keyword| keyword.word == word).map(|keyword| keyword.frequency).unwrap_or(0)
+    };
+
+    let reality = merged.keywords.iter().find(|keyword| keyword.word == "reality").unwrap();
+
+    // "earth" shares a synset with "world", not with "reality"; "world" can
+    // be an adjective and "earth" a verb, so neither is a keyword of its own
+    assert_eq!(reality.variants, vec!["world"]);
+    assert_eq!(reality.occurrences.iter().map(|&(_, span)| &mars[span.start..span.end]).collect::<Vec<&str>>(),
+               vec!["world", "reality", "worlds", "worlds"]);
+    assert_eq!(frequency(&separate, "reality"), 1);
+    assert_eq!(frequency(&separate, "world"), 0);
+    assert!(separate.keywords.iter().all(|keyword| keyword.variants.is_empty()));
+}
+
+#[test]
+fn idf_corpora_skip_hidden_and_binary_files() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+    let corpus = fixtures().join("fixtures/idf-corpus");
+
+    let (idf, warnings) = summary.build_idf(&corpus).unwrap();
+
+    // dose.txt and more/serotonin.txt, latin1.txt is Latin-1
+    assert_eq!(idf.documents(), 2);
+    assert_eq!(idf.document_frequency("dose"), 2);
+    assert_eq!(warnings,
+               vec![format!("{} is hidden, skipped it", corpus.join(".hidden").display()),
+                    format!("{} is not UTF-8 text, skipped it", corpus.join("latin1.txt").display())]);
+
+    let missing = fixtures().join("fixtures/missing");
+    assert!(summary.build_idf(&missing).unwrap_err().to_string().starts_with(&missing.display().to_string()));
+}
+
+#[test]
+fn topics_are_compiled_without_noun_synsets() {
+    let summary = Summary::with_dictionary(fixtures().join("fixtures/dict").to_str().unwrap()).unwrap();
+    let caffeine = read(&fixtures().join("fixtures/corpus/caffeine.txt"));
+
+    // there is no data.noun, only the lexicographer files of the hypernyms of a few words of caffeine.txt
+    assert!(summary.has_topics());
+
+    let digest = summary.digest(&caffeine, &SummaryOptions { num_topics: 3, ..SummaryOptions::default() });
+    let topics = digest.topics.iter().map(|topic| (topic.label.as_str(), topic.keywords.clone())).collect::<Vec<_>>();
+
+    // "compound" and "substance" above it are supported by the same keywords, "heart" and "hour" by none other
+    assert_eq!(topics, vec![("organic compound", vec!["caffeine".to_string(), "xanthine".to_string(), "adenosine".to_string()])]);
+}